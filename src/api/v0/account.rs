@@ -1,4 +1,5 @@
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::BTreeMap;
 use straitjacket_macro::straitjacket;
 
 pub mod application;
@@ -8,11 +9,41 @@ pub mod user;
 
 pub type Metadata = crate::resources::Metadata;
 
+// Some Porta versions render the account id as a string. Shared by the
+// resources that belong to an account, such as its users and features.
+fn parse_account_id<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<u64>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Id {
+        Number(u64),
+        String(String),
+    }
+
+    match Option::<Id>::deserialize(deserializer)? {
+        None => Ok(None),
+        Some(Id::Number(n)) => Ok(Some(n)),
+        Some(Id::String(s)) => s.trim().parse().map(Some).map_err(serde::de::Error::custom),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum State {
+    Created,
+    Pending,
+    Approved,
+    Rejected,
+    Suspended,
+    ScheduledForDeletion,
+    #[serde(other)]
+    Unknown,
+}
+
 #[straitjacket]
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Account {
     id: u64,
-    state: String,
+    state: State,
     org_name: String,
     extra_fields: Option<Vec<String>>,
     monthly_billing_enabled: bool,
@@ -21,7 +52,97 @@ pub struct Account {
     plans: Option<Vec<plan::Plan>>,
 }
 
+impl Account {
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    pub fn org_name(&self) -> &str {
+        self.org_name.as_str()
+    }
+
+    pub fn state(&self) -> State {
+        self.state
+    }
+
+    pub fn monthly_billing_enabled(&self) -> bool {
+        self.monthly_billing_enabled
+    }
+
+    pub fn monthly_charging_enabled(&self) -> bool {
+        self.monthly_charging_enabled
+    }
+
+    pub fn credit_card_stored(&self) -> bool {
+        self.credit_card_stored
+    }
+
+    pub fn plans(&self) -> Option<&[plan::Plan]> {
+        self.plans.as_deref()
+    }
+}
+
+// Query string to find an account by one of its users.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct AccountLookup {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_id: Option<u64>,
+}
+
+impl AccountLookup {
+    pub fn by_username<S: Into<String>>(username: S) -> Self {
+        Self {
+            username: Some(username.into()),
+            ..Default::default()
+        }
+    }
+
+    pub fn by_email<S: Into<String>>(email: S) -> Self {
+        Self {
+            email: Some(email.into()),
+            ..Default::default()
+        }
+    }
+
+    pub fn by_user_id(user_id: u64) -> Self {
+        Self {
+            user_id: Some(user_id),
+            ..Default::default()
+        }
+    }
+}
+
+// Parameters to update an account.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct AccountUpdate {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub org_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub monthly_billing_enabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub monthly_charging_enabled: Option<bool>,
+    // Custom fields defined for accounts in the developer portal.
+    #[serde(flatten)]
+    pub extra_fields: BTreeMap<String, String>,
+}
+
+impl crate::resources::pagination::Paginated for Accounts {
+    type Item = Account;
+}
+
 endpoint! { EP_LIST_ACCOUNTS, GET joining [ "/admin/api/accounts.json" ] returning Accounts }
+// Takes an AccountLookup query string.
+endpoint! { EP_FIND_ACCOUNT, GET joining [ "/admin/api/accounts/find.json" ] returning AccountTag }
+endpoint! { EP_READ_ACCOUNT, GET joining [ "/admin/api/accounts/", ".json" ] returning AccountTag }
+endpoint! { EP_UPDATE_ACCOUNT, PUT joining [ "/admin/api/accounts/", ".json" ] returning AccountTag }
+endpoint! { EP_DELETE_ACCOUNT, DELETE joining [ "/admin/api/accounts/", ".json" ] returning () }
+endpoint! { EP_APPROVE_ACCOUNT, PUT joining [ "/admin/api/accounts/", "/approve.json" ] returning AccountTag }
+endpoint! { EP_REJECT_ACCOUNT, PUT joining [ "/admin/api/accounts/", "/reject.json" ] returning AccountTag }
+endpoint! { EP_MAKE_PENDING_ACCOUNT, PUT joining [ "/admin/api/accounts/", "/make_pending.json" ] returning AccountTag }
 endpoint_test! { it_parses, EP_LIST_ACCOUNTS, r##"{
    "accounts" : [
       {
@@ -255,7 +376,7 @@ mod tests {
     fn it_serializes() {
         let accounts = Accounts::from(vec![Account {
             id: 2445583035585,
-            state: "created".into(),
+            state: State::Created,
             extra_fields: None,
             monthly_billing_enabled: true,
             monthly_charging_enabled: true,
@@ -264,11 +385,54 @@ mod tests {
             org_name: "test-nWG4mvQl_z0".into(),
         }]);
         let result = serde_json::to_string_pretty(&accounts);
-        match result {
-            Err(ref e) => println!("Error: {:#?}", e),
-            _ => (),
+        if let Err(ref e) = result {
+            println!("Error: {:#?}", e);
         }
         assert!(result.is_ok());
         println!("{}", result.unwrap());
     }
+
+    #[test]
+    fn it_parses_states() {
+        let accounts: Vec<Account> = EP_LIST_ACCOUNTS.parse_str(RESPONSE).unwrap().into();
+        assert_eq!(accounts[0].state(), State::Approved);
+        assert_eq!(accounts[3].state(), State::Created);
+
+        let account: Account = serde_json::from_str(
+            r#"{ "id": 1, "state": "on_hold", "org_name": "x", "monthly_billing_enabled": true, "monthly_charging_enabled": true, "credit_card_stored": false }"#,
+        )
+        .unwrap();
+        assert_eq!(account.state(), State::Unknown);
+    }
+
+    #[test]
+    fn it_looks_accounts_up_by_a_single_field() {
+        assert_eq!(
+            EP_FIND_ACCOUNT.path(&[]).unwrap(),
+            "/admin/api/accounts/find.json"
+        );
+        assert_eq!(
+            serde_json::to_value(AccountLookup::by_username("john")).unwrap(),
+            serde_json::json!({ "username": "john" })
+        );
+        assert_eq!(
+            serde_json::to_value(AccountLookup::by_user_id(5)).unwrap(),
+            serde_json::json!({ "user_id": 5 })
+        );
+    }
+
+    #[test]
+    fn it_flattens_extra_fields_in_updates() {
+        let mut update = AccountUpdate {
+            org_name: Some("Org".into()),
+            ..Default::default()
+        };
+        update
+            .extra_fields
+            .insert("vat_code".into(), "ES123".into());
+        assert_eq!(
+            serde_json::to_string(&update).unwrap(),
+            r#"{"org_name":"Org","vat_code":"ES123"}"#
+        );
+    }
 }
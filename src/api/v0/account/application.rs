@@ -1,6 +1,9 @@
 use serde::{Deserialize, Serialize};
 use straitjacket_macro::straitjacket;
 
+pub mod key;
+pub mod referrer_filter;
+
 pub type Metadata = crate::resources::Metadata;
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -32,7 +35,68 @@ pub struct Application {
     pub provider_verification_key: Option<String>,
 }
 
+// Parameters to create an application. Keys are generated by Porta unless
+// given, and which ones apply depends on the service's authentication mode.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct NewApplication {
+    pub plan_id: u64,
+    pub name: String,
+    pub description: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_key: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub application_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub application_key: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub redirect_url: Option<String>,
+}
+
+impl NewApplication {
+    pub fn new<N: Into<String>, D: Into<String>>(plan_id: u64, name: N, description: D) -> Self {
+        Self {
+            plan_id,
+            name: name.into(),
+            description: description.into(),
+            ..Default::default()
+        }
+    }
+}
+
+// Parameters to update an application.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ApplicationUpdate {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+// Moves an application to another plan of the same service.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PlanChange {
+    pub plan_id: u64,
+}
+
+impl PlanChange {
+    pub fn new(plan_id: u64) -> Self {
+        Self { plan_id }
+    }
+}
+
 endpoint! { EP_LIST_APPLICATIONS, GET joining [ "/admin/api/accounts/", "/applications.json" ] returning Applications }
+endpoint! { EP_CREATE_APPLICATION, POST joining [ "/admin/api/accounts/", "/applications.json" ] returning ApplicationTag }
+// The endpoints below take the account id and the application id.
+endpoint! { EP_READ_APPLICATION, GET joining [ "/admin/api/accounts/", "/applications/", ".json" ] returning ApplicationTag }
+endpoint! { EP_UPDATE_APPLICATION, PUT joining [ "/admin/api/accounts/", "/applications/", ".json" ] returning ApplicationTag }
+endpoint! { EP_DELETE_APPLICATION, DELETE joining [ "/admin/api/accounts/", "/applications/", ".json" ] returning () }
+endpoint! { EP_SUSPEND_APPLICATION, PUT joining [ "/admin/api/accounts/", "/applications/", "/suspend.json" ] returning ApplicationTag }
+endpoint! { EP_RESUME_APPLICATION, PUT joining [ "/admin/api/accounts/", "/applications/", "/resume.json" ] returning ApplicationTag }
+// Takes a PlanChange body.
+endpoint! { EP_CHANGE_APPLICATION_PLAN, PUT joining [ "/admin/api/accounts/", "/applications/", "/change_plan.json" ] returning ApplicationTag }
+// Give the application its own copy of its plan, or go back to the original.
+endpoint! { EP_CUSTOMIZE_APPLICATION_PLAN, PUT joining [ "/admin/api/accounts/", "/applications/", "/customize_plan.json" ] returning ApplicationTag }
+endpoint! { EP_DECUSTOMIZE_APPLICATION_PLAN, PUT joining [ "/admin/api/accounts/", "/applications/", "/decustomize_plan.json" ] returning ApplicationTag }
 endpoint_test! { it_parses, EP_LIST_APPLICATIONS, r##"{
   "applications": [
     {
@@ -342,11 +406,34 @@ mod tests {
             provider_verification_key: None,
         }]);
         let result = serde_json::to_string_pretty(&users);
-        match result {
-            Err(ref e) => println!("Error: {:#?}", e),
-            _ => (),
+        if let Err(ref e) = result {
+            println!("Error: {:#?}", e);
         }
         assert!(result.is_ok());
         println!("{}", result.unwrap());
     }
+
+    #[test]
+    fn it_describes_application_plan_changes() {
+        assert_eq!(
+            EP_CHANGE_APPLICATION_PLAN
+                .path(&["2445582571514", "1409618117181"])
+                .unwrap(),
+            "/admin/api/accounts/2445582571514/applications/1409618117181/change_plan.json"
+        );
+        assert_eq!(
+            serde_json::to_string(&PlanChange::new(42)).unwrap(),
+            r#"{"plan_id":42}"#
+        );
+    }
+
+    #[test]
+    fn it_serializes_new_applications() {
+        let mut application = NewApplication::new(42, "App", "An app");
+        application.user_key = Some("abc".into());
+        assert_eq!(
+            serde_json::to_string(&application).unwrap(),
+            r#"{"plan_id":42,"name":"App","description":"An app","user_key":"abc"}"#
+        );
+    }
 }
@@ -0,0 +1,98 @@
+// Application keys, used by services authenticating with an app_id and one
+// of several app_keys, and the single user_key of user_key services.
+use serde::{Deserialize, Serialize};
+use straitjacket_macro::straitjacket;
+
+use crate::api::v0::service::EmptyMetadata;
+
+use super::ApplicationTag;
+
+#[straitjacket(name_snake = "key", plural_snake = "keys", metadata = "EmptyMetadata")]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Key {
+    value: String,
+}
+
+impl Key {
+    pub fn value(&self) -> &str {
+        self.value.as_str()
+    }
+}
+
+// Parameters to add a key. Porta generates a random value if missing.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct NewKey {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+}
+
+impl NewKey {
+    pub fn new<S: Into<String>>(key: S) -> Self {
+        Self {
+            key: Some(key.into()),
+        }
+    }
+
+    pub fn random() -> Self {
+        Self::default()
+    }
+}
+
+// Percent-encode a key value to be used as a path segment, since keys can
+// contain characters such as `/` that would otherwise change the path.
+pub fn key_path_segment(value: &str) -> String {
+    // form_urlencoded encodes spaces as `+`, which is not decoded in paths.
+    url::form_urlencoded::byte_serialize(value.as_bytes())
+        .map(|chunk| if chunk == "+" { "%20" } else { chunk })
+        .collect()
+}
+
+// The endpoints take the account id and the application id.
+endpoint! { EP_LIST_APPLICATION_KEYS, GET joining [ "/admin/api/accounts/", "/applications/", "/keys.json" ] returning Keys }
+endpoint! { EP_CREATE_APPLICATION_KEY, POST joining [ "/admin/api/accounts/", "/applications/", "/keys.json" ] returning ApplicationTag }
+// Also takes the value of the key to delete, as encoded by `key_path_segment`.
+endpoint! { EP_DELETE_APPLICATION_KEY, DELETE joining [ "/admin/api/accounts/", "/applications/", "/keys/", ".json" ] returning () }
+// Replaces the user_key of the application with a new random one.
+endpoint! { EP_REGENERATE_USER_KEY, PUT joining [ "/admin/api/accounts/", "/applications/", "/key/regenerate.json" ] returning ApplicationTag }
+endpoint_test! { it_parses, EP_LIST_APPLICATION_KEYS, r##"{
+  "keys": [
+    {
+      "key": {
+        "value": "2fd4e1c67a2d28fced849ee1bb76e739"
+      }
+    },
+    {
+      "key": {
+        "value": "de9f2c7fd25e1b3afad3e85a0bd17d9b"
+      }
+    }
+  ]
+}"## }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_lists_key_values() {
+        let keys: Vec<Key> = EP_LIST_APPLICATION_KEYS.parse_str(RESPONSE).unwrap().into();
+        let values = keys.iter().map(Key::value).collect::<Vec<_>>();
+        assert_eq!(
+            values,
+            vec![
+                "2fd4e1c67a2d28fced849ee1bb76e739",
+                "de9f2c7fd25e1b3afad3e85a0bd17d9b"
+            ]
+        );
+    }
+
+    #[test]
+    fn it_encodes_keys_as_a_single_path_segment() {
+        let key = key_path_segment("a/b c+d?");
+        assert_eq!(key, "a%2Fb%20c%2Bd%3F");
+        assert_eq!(
+            EP_DELETE_APPLICATION_KEY.path(&["3", "5", &key]).unwrap(),
+            "/admin/api/accounts/3/applications/5/keys/a%2Fb%20c%2Bd%3F.json"
+        );
+    }
+}
@@ -0,0 +1,86 @@
+// Referrer filters restrict the domains an application can be used from.
+use serde::{Deserialize, Serialize};
+use straitjacket_macro::straitjacket;
+
+pub type Metadata = crate::resources::Metadata;
+
+#[straitjacket]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReferrerFilter {
+    id: u64,
+    application_id: Option<u64>,
+    value: String,
+}
+
+impl ReferrerFilter {
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    pub fn application_id(&self) -> Option<u64> {
+        self.application_id
+    }
+
+    pub fn value(&self) -> &str {
+        self.value.as_str()
+    }
+}
+
+// Parameters to add a referrer filter, ie. a domain or a wildcard like
+// "*.example.com".
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct NewReferrerFilter {
+    pub referrer_filter: String,
+}
+
+impl NewReferrerFilter {
+    pub fn new<S: Into<String>>(referrer_filter: S) -> Self {
+        Self {
+            referrer_filter: referrer_filter.into(),
+        }
+    }
+}
+
+// The endpoints take the account id and the application id.
+endpoint! { EP_LIST_REFERRER_FILTERS, GET joining [ "/admin/api/accounts/", "/applications/", "/referrer_filters.json" ] returning ReferrerFilters }
+endpoint! { EP_CREATE_REFERRER_FILTER, POST joining [ "/admin/api/accounts/", "/applications/", "/referrer_filters.json" ] returning ReferrerFilterTag }
+// Also takes the id of the filter.
+endpoint! { EP_DELETE_REFERRER_FILTER, DELETE joining [ "/admin/api/accounts/", "/applications/", "/referrer_filters/", ".json" ] returning () }
+endpoint_test! { it_parses, EP_LIST_REFERRER_FILTERS, r##"{
+  "referrer_filters": [
+    {
+      "referrer_filter": {
+        "id": 2,
+        "application_id": 1409618117181,
+        "value": "*.example.com",
+        "created_at": "2020-07-20T11:24:23+01:00",
+        "updated_at": "2020-07-20T11:24:23+01:00"
+      }
+    }
+  ]
+}"## }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_reads_filter_values() {
+        let filters: Vec<ReferrerFilter> =
+            EP_LIST_REFERRER_FILTERS.parse_str(RESPONSE).unwrap().into();
+        assert_eq!(filters[0].id(), 2);
+        assert_eq!(filters[0].value(), "*.example.com");
+    }
+
+    #[test]
+    fn it_describes_new_filters() {
+        assert_eq!(
+            EP_CREATE_REFERRER_FILTER.path(&["1", "5"]).unwrap(),
+            "/admin/api/accounts/1/applications/5/referrer_filters.json"
+        );
+        assert_eq!(
+            serde_json::to_string(&NewReferrerFilter::new("api.example.com")).unwrap(),
+            r#"{"referrer_filter":"api.example.com"}"#
+        );
+    }
+}
@@ -1,21 +1,140 @@
 use serde::{Deserialize, Serialize};
 use straitjacket_macro::straitjacket;
 
+use super::parse_account_id;
+
 pub type Metadata = crate::resources::Metadata;
 
+// The kind of plan a feature can be enabled on. Account features apply to
+// account plans, while service features apply to application or service plans.
+//
+// Porta renders scopes in snake case, but reads them as the class names of the
+// plans, so unlike most enums here a scope doesn't round-trip through serde:
+// `account_plan` is read as `AccountPlan` and sent as "AccountPlan".
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Scope {
+    #[serde(rename(serialize = "AccountPlan", deserialize = "account_plan"))]
+    AccountPlan,
+    #[serde(rename(serialize = "ApplicationPlan", deserialize = "application_plan"))]
+    ApplicationPlan,
+    #[serde(rename(serialize = "ServicePlan", deserialize = "service_plan"))]
+    ServicePlan,
+    #[serde(other)]
+    Unknown,
+}
+
+// An unknown scope is never sent, so that Porta picks one.
+fn skip_scope(scope: &Option<Scope>) -> bool {
+    matches!(scope, None | Some(Scope::Unknown))
+}
+
 #[straitjacket]
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Feature {
     id: u64,
     name: String,
     system_name: Option<String>,
-    account_id: Option<String>,
-    scope: String,
+    #[serde(default, deserialize_with = "parse_account_id")]
+    account_id: Option<u64>,
+    scope: Scope,
     visible: bool,
     description: Option<String>,
 }
 
+impl Feature {
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    pub fn system_name(&self) -> Option<&str> {
+        self.system_name.as_deref()
+    }
+
+    pub fn account_id(&self) -> Option<u64> {
+        self.account_id
+    }
+
+    pub fn scope(&self) -> Scope {
+        self.scope
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+}
+
+// Parameters to create a feature. Porta picks the scope from where the
+// feature is created if missing.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct NewFeature {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "skip_scope")]
+    pub scope: Option<Scope>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub visible: Option<bool>,
+}
+
+impl NewFeature {
+    pub fn new<S: Into<String>>(name: S) -> Self {
+        Self {
+            name: name.into(),
+            ..Default::default()
+        }
+    }
+}
+
+// Parameters to update a feature.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct FeatureUpdate {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub visible: Option<bool>,
+}
+
+// Enables a feature on a plan.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PlanFeature {
+    pub feature_id: u64,
+}
+
+impl PlanFeature {
+    pub fn new(feature_id: u64) -> Self {
+        Self { feature_id }
+    }
+}
+
 endpoint! { EP_LIST_FEATURES, GET joining [ "/admin/api/features.json"] returning Features }
+endpoint! { EP_READ_FEATURE, GET joining [ "/admin/api/features/", ".json" ] returning FeatureTag }
+endpoint! { EP_CREATE_FEATURE, POST joining [ "/admin/api/features.json" ] returning FeatureTag }
+endpoint! { EP_UPDATE_FEATURE, PUT joining [ "/admin/api/features/", ".json" ] returning FeatureTag }
+endpoint! { EP_DELETE_FEATURE, DELETE joining [ "/admin/api/features/", ".json" ] returning () }
+
+// Features enabled on plans. The endpoints take the plan id, enabling takes a
+// PlanFeature body and disabling also takes the feature id.
+endpoint! { EP_LIST_ACCOUNT_PLAN_FEATURES, GET joining [ "/admin/api/account_plans/", "/features.json" ] returning Features }
+endpoint! { EP_ENABLE_ACCOUNT_PLAN_FEATURE, POST joining [ "/admin/api/account_plans/", "/features.json" ] returning FeatureTag }
+endpoint! { EP_DISABLE_ACCOUNT_PLAN_FEATURE, DELETE joining [ "/admin/api/account_plans/", "/features/", ".json" ] returning () }
+endpoint! { EP_LIST_APPLICATION_PLAN_FEATURES, GET joining [ "/admin/api/application_plans/", "/features.json" ] returning Features }
+endpoint! { EP_ENABLE_APPLICATION_PLAN_FEATURE, POST joining [ "/admin/api/application_plans/", "/features.json" ] returning FeatureTag }
+endpoint! { EP_DISABLE_APPLICATION_PLAN_FEATURE, DELETE joining [ "/admin/api/application_plans/", "/features/", ".json" ] returning () }
+endpoint! { EP_LIST_SERVICE_PLAN_FEATURES, GET joining [ "/admin/api/service_plans/", "/features.json" ] returning Features }
+endpoint! { EP_ENABLE_SERVICE_PLAN_FEATURE, POST joining [ "/admin/api/service_plans/", "/features.json" ] returning FeatureTag }
+endpoint! { EP_DISABLE_SERVICE_PLAN_FEATURE, DELETE joining [ "/admin/api/service_plans/", "/features/", ".json" ] returning () }
 endpoint_test! { it_parses, EP_LIST_FEATURES, r##"{
    "features":[
       {
@@ -67,7 +186,7 @@ mod tests {
                 name: "my_feature".into(),
                 system_name: Some(String::from("my_feature")),
                 account_id: None,
-                scope: "account_plan".into(),
+                scope: Scope::AccountPlan,
                 visible: true,
                 description: None,
             },
@@ -76,17 +195,83 @@ mod tests {
                 name: "my_2nd_feature".into(),
                 system_name: Some(String::from("my_2nd_feature")),
                 visible: false,
-                scope: "account_plan".into(),
+                scope: Scope::AccountPlan,
                 account_id: None,
                 description: None,
             },
         ]);
         let result = serde_json::to_string_pretty(&features);
-        match result {
-            Err(ref e) => println!("Error: {:#?}", e),
-            _ => (),
+        if let Err(ref e) = result {
+            println!("Error: {:#?}", e);
         }
         assert!(result.is_ok());
         println!("{}", result.unwrap());
     }
+
+    #[test]
+    fn it_parses_scopes_and_account_ids() {
+        let features: Vec<Feature> = EP_LIST_FEATURES.parse_str(RESPONSE).unwrap().into();
+        assert_eq!(features[0].scope(), Scope::AccountPlan);
+
+        let feature: Feature = serde_json::from_str(
+            r#"{ "id": 1, "name": "f", "account_id": "2445582571513", "scope": "application_plan", "visible": false }"#,
+        )
+        .unwrap();
+        assert_eq!(feature.account_id(), Some(2445582571513));
+        assert_eq!(feature.scope(), Scope::ApplicationPlan);
+    }
+
+    #[test]
+    fn it_reads_and_sends_scopes_in_porta_formats() {
+        for (read, sent, scope) in [
+            ("account_plan", "AccountPlan", Scope::AccountPlan),
+            (
+                "application_plan",
+                "ApplicationPlan",
+                Scope::ApplicationPlan,
+            ),
+            ("service_plan", "ServicePlan", Scope::ServicePlan),
+        ] {
+            let parsed: Scope = serde_json::from_value(read.into()).unwrap();
+            assert_eq!(parsed, scope);
+            assert_eq!(serde_json::to_value(scope).unwrap(), sent);
+            let class_name: Scope = serde_json::from_value(sent.into()).unwrap();
+            assert_eq!(class_name, Scope::Unknown);
+        }
+    }
+
+    #[test]
+    fn it_sends_scopes_as_plan_class_names() {
+        let mut feature = NewFeature::new("premium_support");
+        feature.scope = Some(Scope::ServicePlan);
+        assert_eq!(
+            serde_json::to_string(&feature).unwrap(),
+            r#"{"name":"premium_support","scope":"ServicePlan"}"#
+        );
+        feature.scope = Some(Scope::Unknown);
+        assert_eq!(
+            serde_json::to_string(&feature).unwrap(),
+            r#"{"name":"premium_support"}"#
+        );
+    }
+
+    #[test]
+    fn it_describes_plan_feature_changes() {
+        assert_eq!(
+            EP_ENABLE_APPLICATION_PLAN_FEATURE
+                .path(&["2357355970342"])
+                .unwrap(),
+            "/admin/api/application_plans/2357355970342/features.json"
+        );
+        assert_eq!(
+            EP_DISABLE_APPLICATION_PLAN_FEATURE
+                .path(&["2357355970342", "4142037"])
+                .unwrap(),
+            "/admin/api/application_plans/2357355970342/features/4142037.json"
+        );
+        assert_eq!(
+            serde_json::to_string(&PlanFeature::new(4142037)).unwrap(),
+            r#"{"feature_id":4142037}"#
+        );
+    }
 }
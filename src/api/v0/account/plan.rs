@@ -3,15 +3,8 @@ use straitjacket_macro::straitjacket;
 
 pub type Metadata = crate::resources::Metadata;
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-pub enum State {
-    #[serde(rename(serialize = "publish", deserialize = "published"))]
-    Published,
-    #[serde(rename(serialize = "hide", deserialize = "hidden"))]
-    Hidden,
-    #[serde(other)]
-    Unknown,
-}
+// Account plans share their state and parameters with application plans.
+pub use crate::api::v0::service::plan::{NewPlan, PlanUpdate, State, StateEvent};
 
 #[straitjacket(name_snake = "account_plan")]
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -31,6 +24,52 @@ pub struct Plan {
     cancellation_period: Option<u64>,
 }
 
+impl Plan {
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    pub fn state(&self) -> State {
+        self.state
+    }
+
+    pub fn is_published(&self) -> bool {
+        self.state == State::Published
+    }
+
+    pub fn is_default(&self) -> bool {
+        self.default.unwrap_or(false)
+    }
+
+    pub fn is_custom(&self) -> bool {
+        self.custom.unwrap_or(false)
+    }
+
+    pub fn approval_required(&self) -> bool {
+        self.approval_required
+    }
+
+    pub fn setup_fee(&self) -> f64 {
+        self.setup_fee
+    }
+
+    pub fn cost_per_month(&self) -> f64 {
+        self.cost_per_month
+    }
+}
+
+endpoint! { EP_LIST_ACCOUNT_PLANS, GET joining [ "/admin/api/account_plans.json" ] returning Plans }
+endpoint! { EP_READ_ACCOUNT_PLAN, GET joining [ "/admin/api/account_plans/", ".json" ] returning PlanTag }
+endpoint! { EP_CREATE_ACCOUNT_PLAN, POST joining [ "/admin/api/account_plans.json" ] returning PlanTag }
+endpoint! { EP_UPDATE_ACCOUNT_PLAN, PUT joining [ "/admin/api/account_plans/", ".json" ] returning PlanTag }
+endpoint! { EP_DELETE_ACCOUNT_PLAN, DELETE joining [ "/admin/api/account_plans/", ".json" ] returning () }
+// Make the plan the default one for new accounts.
+endpoint! { EP_SET_DEFAULT_ACCOUNT_PLAN, PUT joining [ "/admin/api/account_plans/", "/default.json" ] returning PlanTag }
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -58,7 +97,19 @@ mod test {
   ]
 }
         "##;
-        let plans: Result<Plans, _> = serde_json::from_str(&body);
+        let plans: Result<Plans, _> = serde_json::from_str(body);
         assert!(plans.is_ok());
+        let plans: Vec<Plan> = plans.unwrap().into();
+        assert!(plans[0].is_default());
+        assert_eq!(plans[0].state(), State::Hidden);
+    }
+
+    #[test]
+    fn it_builds_default_plan_paths() {
+        assert_eq!(
+            EP_SET_DEFAULT_ACCOUNT_PLAN.path(&["3"]).unwrap(),
+            "/admin/api/account_plans/3/default.json"
+        );
+        assert_eq!(EP_SET_DEFAULT_ACCOUNT_PLAN.method(), &http::Method::PUT);
     }
 }
@@ -1,21 +1,123 @@
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use straitjacket_macro::straitjacket;
 
+use super::parse_account_id;
+
 pub type Metadata = crate::resources::Metadata;
 
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum State {
+    Pending,
+    Active,
+    Suspended,
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    Admin,
+    Member,
+    #[serde(other)]
+    Unknown,
+}
+
 #[straitjacket]
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct User {
     id: u64,
-    account_id: Option<String>,
-    state: String,
-    role: String,
+    #[serde(default, deserialize_with = "parse_account_id")]
+    account_id: Option<u64>,
+    state: State,
+    role: Role,
     username: String,
     email: String,
     extra_fields: Option<Vec<String>>,
 }
 
+impl User {
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    pub fn account_id(&self) -> Option<u64> {
+        self.account_id
+    }
+
+    pub fn state(&self) -> State {
+        self.state
+    }
+
+    pub fn role(&self) -> Role {
+        self.role
+    }
+
+    pub fn is_admin(&self) -> bool {
+        self.role == Role::Admin
+    }
+
+    pub fn username(&self) -> &str {
+        self.username.as_str()
+    }
+
+    pub fn email(&self) -> &str {
+        self.email.as_str()
+    }
+}
+
+// Parameters to create a user in an account. New users start as pending.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct NewUser {
+    pub username: String,
+    pub email: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+    // Custom fields defined for users in the developer portal.
+    #[serde(flatten)]
+    pub extra_fields: BTreeMap<String, String>,
+}
+
+impl NewUser {
+    pub fn new<S: Into<String>, E: Into<String>>(username: S, email: E) -> Self {
+        Self {
+            username: username.into(),
+            email: email.into(),
+            ..Default::default()
+        }
+    }
+}
+
+// Parameters to update a user.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct UserUpdate {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+    #[serde(flatten)]
+    pub extra_fields: BTreeMap<String, String>,
+}
+
+impl crate::resources::pagination::Paginated for Users {
+    type Item = User;
+}
+
 endpoint! { EP_LIST_USERS, GET joining [ "/admin/api/accounts/", "/users.json" ] returning Users }
+endpoint! { EP_CREATE_USER, POST joining [ "/admin/api/accounts/", "/users.json" ] returning UserTag }
+// The endpoints below take the account id and the user id.
+endpoint! { EP_READ_USER, GET joining [ "/admin/api/accounts/", "/users/", ".json" ] returning UserTag }
+endpoint! { EP_UPDATE_USER, PUT joining [ "/admin/api/accounts/", "/users/", ".json" ] returning UserTag }
+endpoint! { EP_DELETE_USER, DELETE joining [ "/admin/api/accounts/", "/users/", ".json" ] returning () }
+endpoint! { EP_ACTIVATE_USER, PUT joining [ "/admin/api/accounts/", "/users/", "/activate.json" ] returning UserTag }
+endpoint! { EP_SUSPEND_USER, PUT joining [ "/admin/api/accounts/", "/users/", "/suspend.json" ] returning UserTag }
+endpoint! { EP_UNSUSPEND_USER, PUT joining [ "/admin/api/accounts/", "/users/", "/unsuspend.json" ] returning UserTag }
+endpoint! { EP_MAKE_ADMIN_USER, PUT joining [ "/admin/api/accounts/", "/users/", "/admin.json" ] returning UserTag }
+endpoint! { EP_MAKE_MEMBER_USER, PUT joining [ "/admin/api/accounts/", "/users/", "/member.json" ] returning UserTag }
 endpoint_test! { it_parses, EP_LIST_USERS, r##"{
    "users" : [
       {
@@ -51,18 +153,58 @@ mod tests {
         let users = Users::from(vec![User {
             id: 2445583035585,
             account_id: None,
-            state: "active".into(),
-            role: "admin".into(),
+            state: State::Active,
+            role: Role::Admin,
             username: "goluser".into(),
             email: "goluser@flawedcode.org".into(),
             extra_fields: None,
         }]);
         let result = serde_json::to_string_pretty(&users);
-        match result {
-            Err(ref e) => println!("Error: {:#?}", e),
-            _ => (),
+        if let Err(ref e) = result {
+            println!("Error: {:#?}", e);
         }
         assert!(result.is_ok());
         println!("{}", result.unwrap());
     }
+
+    #[test]
+    fn it_parses_roles_states_and_account_ids() {
+        let users: Vec<User> = EP_LIST_USERS.parse_str(RESPONSE).unwrap().into();
+        assert_eq!(users[0].state(), State::Active);
+        assert!(users[0].is_admin());
+        assert_eq!(users[0].account_id(), None);
+
+        for account_id in [r#"2445582851727"#, r#""2445582851727""#] {
+            let user: User = serde_json::from_str(&format!(
+                r#"{{ "id": 1, "account_id": {}, "state": "blocked", "role": "member", "username": "u", "email": "u@example.com" }}"#,
+                account_id
+            ))
+            .unwrap();
+            assert_eq!(user.account_id(), Some(2445582851727));
+            assert_eq!(user.state(), State::Unknown);
+            assert_eq!(user.role(), Role::Member);
+        }
+    }
+
+    #[test]
+    fn it_builds_user_state_change_paths() {
+        assert_eq!(
+            EP_SUSPEND_USER.path(&["3", "5"]).unwrap(),
+            "/admin/api/accounts/3/users/5/suspend.json"
+        );
+        assert_eq!(
+            EP_UNSUSPEND_USER.path(&["3", "5"]).unwrap(),
+            "/admin/api/accounts/3/users/5/unsuspend.json"
+        );
+    }
+
+    #[test]
+    fn it_serializes_new_users() {
+        let mut user = NewUser::new("john", "john@example.com");
+        user.extra_fields.insert("phone".into(), "555".into());
+        assert_eq!(
+            serde_json::to_string(&user).unwrap(),
+            r#"{"username":"john","email":"john@example.com","phone":"555"}"#
+        );
+    }
 }
@@ -1,6 +1,10 @@
 use serde::{Deserialize, Serialize};
 use straitjacket_macro::straitjacket;
 
+pub mod openapi;
+
+pub use openapi::OpenApiDocument;
+
 pub type Metadata = crate::resources::Metadata;
 
 #[straitjacket]
@@ -12,9 +16,122 @@ pub struct ApiDoc {
     published: bool,
     skip_swagger_validations: bool,
     body: String,
+    description: Option<String>,
+    // The service the docs belong to, if any.
+    service_id: Option<u64>,
+}
+
+impl ApiDoc {
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    pub fn system_name(&self) -> &str {
+        self.system_name.as_str()
+    }
+
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    pub fn is_published(&self) -> bool {
+        self.published
+    }
+
+    pub fn skip_swagger_validations(&self) -> bool {
+        self.skip_swagger_validations
+    }
+
+    pub fn body(&self) -> &str {
+        self.body.as_str()
+    }
+
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
+    pub fn service_id(&self) -> Option<u64> {
+        self.service_id
+    }
+
+    pub fn document(&self) -> crate::error::Result<OpenApiDocument> {
+        OpenApiDocument::parse(self.body.as_str())
+    }
+}
+
+// Parameters to create ActiveDocs.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct NewApiDoc {
+    pub name: String,
+    pub body: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub published: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub skip_swagger_validations: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub service_id: Option<u64>,
+}
+
+impl NewApiDoc {
+    pub fn new<N: Into<String>, B: Into<String>>(name: N, body: B) -> Self {
+        Self {
+            name: name.into(),
+            body: body.into(),
+            ..Default::default()
+        }
+    }
+}
+
+// Parameters to update ActiveDocs.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ApiDocUpdate {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub published: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub skip_swagger_validations: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub service_id: Option<u64>,
+}
+
+impl ApiDocUpdate {
+    pub fn publish() -> Self {
+        Self {
+            published: Some(true),
+            ..Default::default()
+        }
+    }
+
+    pub fn hide() -> Self {
+        Self {
+            published: Some(false),
+            ..Default::default()
+        }
+    }
+
+    // Associates the docs with a service.
+    pub fn service(service_id: u64) -> Self {
+        Self {
+            service_id: Some(service_id),
+            ..Default::default()
+        }
+    }
 }
 
 endpoint! { EP_LIST_API_DOCS, GET joining [ "/admin/api/active_docs.json"] returning ApiDocs }
+endpoint! { EP_READ_API_DOC, GET joining [ "/admin/api/active_docs/", ".json" ] returning ApiDocTag }
+endpoint! { EP_CREATE_API_DOC, POST joining [ "/admin/api/active_docs.json" ] returning ApiDocTag }
+endpoint! { EP_UPDATE_API_DOC, PUT joining [ "/admin/api/active_docs/", ".json" ] returning ApiDocTag }
+endpoint! { EP_DELETE_API_DOC, DELETE joining [ "/admin/api/active_docs/", ".json" ] returning () }
 endpoint_test! { it_parses, EP_LIST_API_DOCS, r##"{
    "api_docs" : [
       {
@@ -46,13 +163,37 @@ mod tests {
             published: true,
             skip_swagger_validations: false,
             body: "{}".into(),
+            description: None,
+            service_id: None,
         }]);
         let result = serde_json::to_string_pretty(&docs);
-        match result {
-            Err(ref e) => println!("Error: {:#?}", e),
-            _ => (),
+        if let Err(ref e) = result {
+            println!("Error: {:#?}", e);
         }
         assert!(result.is_ok());
         println!("{}", result.unwrap());
     }
+
+    #[test]
+    fn it_parses_swagger_2_bodies() {
+        let docs: Vec<ApiDoc> = EP_LIST_API_DOCS.parse_str(RESPONSE).unwrap().into();
+        let document = docs[0].document().unwrap();
+        assert_eq!(document.version(), openapi::SpecVersion::Swagger2);
+        assert_eq!(document.title(), "Echo API");
+        assert_eq!(document.base_path(), "/");
+        let operations = document
+            .operations()
+            .iter()
+            .map(|op| op.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(operations, vec!["GET /", "GET /{echo}"]);
+    }
+
+    #[test]
+    fn it_serializes_service_associations() {
+        assert_eq!(
+            serde_json::to_string(&ApiDocUpdate::service(2555417764324)).unwrap(),
+            r#"{"service_id":2555417764324}"#
+        );
+    }
 }
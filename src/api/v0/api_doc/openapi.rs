@@ -0,0 +1,300 @@
+// A read-only view of the OpenAPI (Swagger 2 or OpenAPI 3) document held in
+// an ActiveDocs body, covering what is needed to check it against the
+// mapping rules of a product: its operations and where they are served.
+use serde_json::Value;
+use std::fmt;
+
+use crate::api::v0::service::proxy::mapping_rules::MappingRule;
+use crate::error::{Error, Result};
+
+const METHODS: &[&str] = &[
+    "get", "put", "post", "delete", "options", "head", "patch", "trace",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SpecVersion {
+    Swagger2,
+    OpenApi3,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Operation {
+    method: String,
+    path: String,
+    operation_id: Option<String>,
+}
+
+impl Operation {
+    // Upper case, as in mapping rules.
+    pub fn method(&self) -> &str {
+        self.method.as_str()
+    }
+
+    // Path relative to the document's base path.
+    pub fn path(&self) -> &str {
+        self.path.as_str()
+    }
+
+    pub fn operation_id(&self) -> Option<&str> {
+        self.operation_id.as_deref()
+    }
+}
+
+impl fmt::Display for Operation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.method, self.path)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct OpenApiDocument {
+    version: SpecVersion,
+    title: String,
+    base_path: String,
+    operations: Vec<Operation>,
+    document: Value,
+}
+
+impl OpenApiDocument {
+    pub fn parse(body: &str) -> Result<Self> {
+        let deserializer = &mut serde_json::Deserializer::from_str(body);
+        let document: Value = serde_path_to_error::deserialize(deserializer)?;
+
+        let version = if document["swagger"].as_str() == Some("2.0") {
+            SpecVersion::Swagger2
+        } else if document["openapi"]
+            .as_str()
+            .is_some_and(|v| v.starts_with("3."))
+        {
+            SpecVersion::OpenApi3
+        } else {
+            return Err(Error::InvalidApiDoc(
+                "missing a supported swagger or openapi version".into(),
+            ));
+        };
+        let paths = document["paths"]
+            .as_object()
+            .ok_or_else(|| Error::InvalidApiDoc("missing paths".into()))?;
+
+        let operations = paths
+            .iter()
+            .flat_map(|(path, item)| {
+                METHODS.iter().filter_map(move |method| {
+                    item.get(*method).map(|operation| Operation {
+                        method: method.to_uppercase(),
+                        path: path.clone(),
+                        operation_id: operation["operationId"].as_str().map(String::from),
+                    })
+                })
+            })
+            .collect();
+        let base_path = match version {
+            SpecVersion::Swagger2 => document["basePath"].as_str().unwrap_or("/").to_string(),
+            // Only the first server is taken into account.
+            SpecVersion::OpenApi3 => document["servers"][0]["url"]
+                .as_str()
+                .map_or_else(|| "/".to_string(), server_path),
+        };
+
+        Ok(Self {
+            version,
+            title: document["info"]["title"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string(),
+            base_path,
+            operations,
+            document,
+        })
+    }
+
+    pub fn version(&self) -> SpecVersion {
+        self.version
+    }
+
+    pub fn title(&self) -> &str {
+        self.title.as_str()
+    }
+
+    // The path operations are served under: `basePath` for Swagger 2, or the
+    // path of the first server's URL for OpenAPI 3, ignoring other servers.
+    // OpenAPI 3 resolves relative server URLs such as `v1` against where the
+    // document is hosted, which is unknown here, so they are taken as relative
+    // to the root instead.
+    pub fn base_path(&self) -> &str {
+        self.base_path.as_str()
+    }
+
+    pub fn operations(&self) -> &[Operation] {
+        self.operations.as_slice()
+    }
+
+    // The whole document, for anything not covered here.
+    pub fn document(&self) -> &Value {
+        &self.document
+    }
+
+    // The path an operation is served at, including the base path.
+    pub fn full_path(&self, operation: &Operation) -> String {
+        format!("{}{}", self.base_path.trim_end_matches('/'), operation.path)
+    }
+
+    // Operations that no mapping rule would match, and so that would be
+    // rejected by the gateway.
+    pub fn unmapped_operations(&self, rules: &[MappingRule]) -> Vec<&Operation> {
+        self.operations
+            .iter()
+            .filter(|operation| {
+                let path = self.full_path(operation);
+                !rules.iter().any(|rule| {
+                    rule.http_method.eq_ignore_ascii_case(&operation.method)
+                        && pattern_matches(&rule.pattern, &path)
+                })
+            })
+            .collect()
+    }
+}
+
+// Server URLs can be absolute or relative to the document.
+fn server_path(url: &str) -> String {
+    match url::Url::parse(url) {
+        Ok(url) => url.path().to_string(),
+        Err(_) if url.starts_with('/') => url.to_string(),
+        Err(_) => format!("/{}", url),
+    }
+}
+
+// Matches a mapping rule pattern against a templated path segment by segment.
+// Placeholders in the pattern match any text within a segment, as they do in
+// the gateway, while placeholders in the path are only matched by pattern
+// placeholders, since a literal rule doesn't cover every value they can take.
+// Like the gateway's regular expressions, patterns match as a prefix of the
+// path, possibly ending within a segment, unless anchored with a trailing '$'.
+// Query string parameters in the pattern are ignored.
+fn pattern_matches(pattern: &str, path: &str) -> bool {
+    let pattern = pattern.split('?').next().unwrap_or_default();
+    let (pattern, exact) = match pattern.strip_suffix('$') {
+        Some(pattern) => (pattern, true),
+        None => (pattern, false),
+    };
+    let segments = |s: &'_ str| {
+        s.split('/')
+            .filter(|segment| !segment.is_empty())
+            .map(String::from)
+            .collect::<Vec<_>>()
+    };
+    let (pattern, path) = (segments(pattern), segments(path));
+
+    if pattern.len() > path.len() || (exact && pattern.len() != path.len()) {
+        return false;
+    }
+    let last = pattern.len().saturating_sub(1);
+    pattern
+        .iter()
+        .zip(path.iter())
+        .enumerate()
+        .all(|(i, (p, s))| segment_matches(p, s, !exact && i == last))
+}
+
+// A placeholder matches one or more characters, and anything else must be
+// the same on both sides. With `prefix`, the pattern only needs to match the
+// start of the segment.
+fn segment_matches(pattern: &str, segment: &str, prefix: bool) -> bool {
+    if let Some(rest) = pattern.strip_prefix('{') {
+        if let Some(end) = rest.find('}') {
+            let rest = &rest[end + 1..];
+            return (1..=segment.len())
+                .filter(|&i| segment.is_char_boundary(i))
+                .any(|i| segment_matches(rest, &segment[i..], prefix));
+        }
+    }
+    match (pattern.chars().next(), segment.chars().next()) {
+        (None, None) => true,
+        (None, Some(_)) => prefix,
+        (Some(p), Some(s)) if p == s => {
+            segment_matches(&pattern[p.len_utf8()..], &segment[s.len_utf8()..], prefix)
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(http_method: &str, pattern: &str) -> MappingRule {
+        MappingRule {
+            http_method: http_method.into(),
+            pattern: pattern.into(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn it_parses_openapi_3_documents() {
+        let document = OpenApiDocument::parse(
+            r#"{
+              "openapi": "3.0.2",
+              "info": { "title": "Books", "version": "1" },
+              "servers": [ { "url": "https://books.example.com/v1" } ],
+              "paths": {
+                "/books": { "get": { "operationId": "list" }, "post": {} },
+                "/books/{bookId}": { "parameters": [], "delete": {} }
+              }
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(document.version(), SpecVersion::OpenApi3);
+        assert_eq!(document.title(), "Books");
+        assert_eq!(document.base_path(), "/v1");
+        let operations = document
+            .operations()
+            .iter()
+            .map(|op| format!("{} {}", document.full_path(op), op.method()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            operations,
+            vec![
+                "/v1/books GET",
+                "/v1/books POST",
+                "/v1/books/{bookId} DELETE"
+            ]
+        );
+        assert_eq!(document.operations()[0].operation_id(), Some("list"));
+
+        let rules = vec![rule("GET", "/v1/books$"), rule("DELETE", "/v1/books/{id}")];
+        let unmapped = document.unmapped_operations(&rules);
+        assert_eq!(unmapped.len(), 1);
+        assert_eq!(unmapped[0].to_string(), "POST /books");
+    }
+
+    #[test]
+    fn it_rejects_other_documents() {
+        let result = OpenApiDocument::parse(r#"{ "info": {}, "paths": {} }"#);
+        assert!(matches!(result, Err(Error::InvalidApiDoc(_))));
+        let result = OpenApiDocument::parse("swagger: '2.0'");
+        assert!(matches!(result, Err(Error::Deserialization { .. })));
+    }
+
+    #[test]
+    fn it_matches_mapping_rule_patterns() {
+        assert!(pattern_matches("/", "/anything/{id}"));
+        assert!(pattern_matches("/books", "/books/{id}"));
+        assert!(!pattern_matches("/books$", "/books/{id}"));
+        assert!(pattern_matches("/books/{id}$", "/books/{bookId}"));
+        assert!(pattern_matches("/books/{id}?format={f}", "/books/1"));
+        assert!(!pattern_matches("/authors", "/books"));
+        assert!(!pattern_matches("/books/1$", "/books/{id}"));
+        assert!(!pattern_matches("/books/1", "/books/{id}/reviews"));
+        assert!(pattern_matches("/books/{id}.json$", "/books/{bookId}.json"));
+        assert!(pattern_matches("/books/{id}.json", "/books/1.json"));
+        assert!(!pattern_matches("/books/{id}.json$", "/books/1.xml"));
+        assert!(!pattern_matches("/books/{id}.json", "/books/.json"));
+        assert!(pattern_matches("/book", "/books"));
+        assert!(pattern_matches("/v1/books", "/v1/books.json"));
+        assert!(!pattern_matches("/v1/books$", "/v1/books.json"));
+        assert!(!pattern_matches("/books$", "/books/1"));
+        assert!(!pattern_matches("/books/{id}.json", "/books/1.xml"));
+        assert!(pattern_matches("/books/v{major}.{minor}", "/books/v1.2"));
+    }
+}
@@ -1,19 +1,169 @@
+pub use crate::api::v0::account::application::*;
+
 use serde::{Deserialize, Serialize};
 
-pub type Metadata = crate::resources::Metadata;
+use crate::api::v0::account::Account;
+use crate::api::v0::service::plan::Plan;
+
+// Query string to find an application by its credentials or id. Porta
+// requires the service when looking up by app_id or by id, and it also
+// disambiguates user_keys shared among services.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ApplicationLookup {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub application_id: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_key: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub app_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub service_id: Option<u64>,
+}
+
+impl ApplicationLookup {
+    pub fn by_user_key<S: Into<String>>(user_key: S) -> Self {
+        Self {
+            user_key: Some(user_key.into()),
+            ..Default::default()
+        }
+    }
+
+    pub fn by_app_id<S: Into<String>>(service_id: u64, app_id: S) -> Self {
+        Self {
+            app_id: Some(app_id.into()),
+            service_id: Some(service_id),
+            ..Default::default()
+        }
+    }
+
+    pub fn by_id(service_id: u64, application_id: u64) -> Self {
+        Self {
+            application_id: Some(application_id),
+            service_id: Some(service_id),
+            ..Default::default()
+        }
+    }
+
+    pub fn in_service(mut self, service_id: u64) -> Self {
+        self.service_id = Some(service_id);
+        self
+    }
+}
+
+// An application along with the account owning it and its plan.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ApplicationDetails {
+    application: Application,
+    account: Account,
+    plan: Plan,
+}
+
+impl ApplicationDetails {
+    pub fn application(&self) -> &Application {
+        &self.application
+    }
+
+    pub fn account(&self) -> &Account {
+        &self.account
+    }
+
+    pub fn plan(&self) -> &Plan {
+        &self.plan
+    }
+
+    #[cfg(feature = "client")]
+    pub fn find(
+        client: &crate::client::Client,
+        lookup: &ApplicationLookup,
+    ) -> crate::error::Result<Self> {
+        use crate::api::v0::{account::EP_READ_ACCOUNT, service::plan::READ};
+
+        let application: Application = client
+            .call(&EP_FIND_APPLICATION, &[], Some(lookup), None::<&()>)?
+            .into();
+        let (account_id, service_id, plan_id) = (
+            application.account_id.to_string(),
+            application.service_id.to_string(),
+            application.plan_id.to_string(),
+        );
+        let account = client
+            .call(&EP_READ_ACCOUNT, &[&account_id], None::<&()>, None::<&()>)?
+            .into();
+        let plan = client
+            .call(&READ, &[&service_id, &plan_id], None::<&()>, None::<&()>)?
+            .into();
 
+        Ok(Self {
+            application,
+            account,
+            plan,
+        })
+    }
+
+    #[cfg(feature = "async-client")]
+    pub async fn find_async(
+        client: &crate::client::AsyncClient,
+        lookup: &ApplicationLookup,
+    ) -> crate::error::Result<Self> {
+        use crate::api::v0::{account::EP_READ_ACCOUNT, service::plan::READ};
+
+        let application: Application = client
+            .call(&EP_FIND_APPLICATION, &[], Some(lookup), None::<&()>)
+            .await?
+            .into();
+        let (account_id, service_id, plan_id) = (
+            application.account_id.to_string(),
+            application.service_id.to_string(),
+            application.plan_id.to_string(),
+        );
+        let account = client
+            .call(&EP_READ_ACCOUNT, &[&account_id], None::<&()>, None::<&()>)
+            .await?
+            .into();
+        let plan = client
+            .call(&READ, &[&service_id, &plan_id], None::<&()>, None::<&()>)
+            .await?
+            .into();
+
+        Ok(Self {
+            application,
+            account,
+            plan,
+        })
+    }
+}
+
+// The applications of all accounts. Unlike those of a single account, which
+// Porta returns at once, these come in pages.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "snake_case")]
-pub enum State {
-    Live,
-    Suspended,
-    #[serde(other)]
-    Unknown,
+#[serde(transparent)]
+pub struct AllApplications(Applications);
+
+impl From<Applications> for AllApplications {
+    fn from(applications: Applications) -> Self {
+        Self(applications)
+    }
 }
 
-pub use crate::api::v0::account::application::*;
+impl From<AllApplications> for Applications {
+    fn from(applications: AllApplications) -> Self {
+        applications.0
+    }
+}
 
-endpoint! { EP_LIST_ALL_APPLICATIONS, GET joining [ "/admin/api/applications.json" ] returning Applications }
+impl From<AllApplications> for Vec<Application> {
+    fn from(applications: AllApplications) -> Self {
+        applications.0.into()
+    }
+}
+
+impl crate::resources::pagination::Paginated for AllApplications {
+    type Item = Application;
+}
+
+endpoint! { EP_LIST_ALL_APPLICATIONS, GET joining [ "/admin/api/applications.json" ] returning AllApplications }
+// Takes an ApplicationLookup query string.
+endpoint! { EP_FIND_APPLICATION, GET joining [ "/admin/api/applications/find.json" ] returning ApplicationTag }
 
 #[cfg(test)]
 mod tests {
@@ -324,11 +474,58 @@ mod tests {
             provider_verification_key: None,
         }]);
         let result = serde_json::to_string_pretty(&users);
-        match result {
-            Err(ref e) => println!("Error: {:#?}", e),
-            _ => (),
+        if let Err(ref e) = result {
+            println!("Error: {:#?}", e);
         }
         assert!(result.is_ok());
         println!("{}", result.unwrap());
     }
+
+    #[cfg(feature = "client")]
+    #[test]
+    fn it_finds_applications_with_their_account_and_plan() {
+        use crate::client::test_server::TestServer;
+
+        let applications: Vec<Application> =
+            EP_LIST_ALL_APPLICATIONS.parse_str(RESPONSE).unwrap().into();
+        let application = ApplicationTag::from(applications[0].clone());
+        let (server, client) = TestServer::with_client(vec![
+            (200, serde_json::to_string(&application).unwrap()),
+            (
+                200,
+                r#"{ "account": { "id": 2445582571514, "state": "approved", "org_name": "Developer", "monthly_billing_enabled": true, "monthly_charging_enabled": true, "credit_card_stored": false } }"#.into(),
+            ),
+            (
+                200,
+                r#"{ "application_plan": { "id": 2357355970342, "name": "app-plan", "state": "published", "setup_fee": 0, "cost_per_month": 0, "approval_required": false } }"#.into(),
+            ),
+        ]);
+        let lookup = ApplicationLookup::by_user_key("8941a95ad91100daf349a379541957ba");
+        let details = ApplicationDetails::find(&client, &lookup);
+        let details = details.unwrap();
+        assert_eq!(details.application().id, 1409618117181);
+        assert_eq!(details.account().org_name(), "Developer");
+        assert_eq!(details.plan().name(), "app-plan");
+
+        let request = server.received();
+        assert_eq!(request.path_only(), "/admin/api/applications/find.json");
+        assert!(request.has_query_pair("user_key", "8941a95ad91100daf349a379541957ba"));
+        assert_eq!(
+            server.received().path_only(),
+            "/admin/api/accounts/2445582571514.json"
+        );
+        assert_eq!(
+            server.received().path_only(),
+            "/admin/api/services/2555417764324/application_plans/2357355970342.json"
+        );
+    }
+
+    #[test]
+    fn it_serializes_lookups() {
+        let lookup = ApplicationLookup::by_app_id(2555417764324, "a1b2c3");
+        assert_eq!(
+            serde_json::to_string(&lookup).unwrap(),
+            r#"{"app_id":"a1b2c3","service_id":2555417764324}"#
+        );
+    }
 }
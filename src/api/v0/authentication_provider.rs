@@ -1,9 +1,12 @@
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Serialize, Serializer};
+use std::fmt;
 use straitjacket_macro::straitjacket;
 
+use crate::error::{Error, Result};
+
 pub type Metadata = crate::resources::Metadata;
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum AuthenticationProviderKind {
     #[serde(rename = "auth0")]
     Auth0,
@@ -13,8 +16,31 @@ pub enum AuthenticationProviderKind {
     Unknown,
 }
 
+// Porta can't create providers of a kind this crate doesn't know, so sending
+// one is an error rather than a request for an "Unknown" kind.
+fn serialize_kind<S: Serializer>(
+    kind: &AuthenticationProviderKind,
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error> {
+    match kind {
+        AuthenticationProviderKind::Unknown => Err(serde::ser::Error::custom(
+            "unknown authentication provider kind",
+        )),
+        kind => kind.serialize(serializer),
+    }
+}
+
+// Stands in for client secrets in Debug output.
+struct Redacted;
+
+impl fmt::Debug for Redacted {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("<redacted>")
+    }
+}
+
 #[straitjacket]
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
 pub struct AuthenticationProvider {
     id: u64,
     account_id: u64,
@@ -35,7 +61,266 @@ pub struct AuthenticationProvider {
     trust_email: bool,
 }
 
+impl AuthenticationProvider {
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    pub fn kind(&self) -> AuthenticationProviderKind {
+        self.kind
+    }
+
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    pub fn system_name(&self) -> &str {
+        self.system_name.as_str()
+    }
+
+    pub fn client_id(&self) -> &str {
+        self.client_id.as_str()
+    }
+
+    pub fn site(&self) -> &str {
+        self.site.as_str()
+    }
+
+    pub fn is_published(&self) -> bool {
+        self.published
+    }
+
+    pub fn callback_url(&self) -> &str {
+        self.callback_url.as_str()
+    }
+}
+
+impl fmt::Debug for AuthenticationProvider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AuthenticationProvider")
+            .field("id", &self.id)
+            .field("account_id", &self.account_id)
+            .field("kind", &self.kind)
+            .field(
+                "automatically_approve_accounts",
+                &self.automatically_approve_accounts,
+            )
+            .field("authorize_url", &self.authorize_url)
+            .field("name", &self.name)
+            .field("system_name", &self.system_name)
+            .field("client_id", &self.client_id)
+            .field("client_secret", &Redacted)
+            .field("site", &self.site)
+            .field(
+                "skip_ssl_certificate_verification",
+                &self.skip_ssl_certificate_verification,
+            )
+            .field("published", &self.published)
+            .field("username_key", &self.username_key)
+            .field("account_type", &self.account_type)
+            .field("callback_url", &self.callback_url)
+            .field("identifier_key", &self.identifier_key)
+            .field("trust_email", &self.trust_email)
+            .finish()
+    }
+}
+
+// Parameters to create an authentication provider. Porta only checks the
+// settings when the provider is used to sign in, so call `validate` before
+// sending them. Providers of an unknown kind can't be sent.
+#[derive(Clone, PartialEq, Serialize)]
+pub struct NewAuthenticationProvider {
+    #[serde(serialize_with = "serialize_kind")]
+    pub kind: AuthenticationProviderKind,
+    pub client_id: String,
+    pub client_secret: String,
+    // For KeyCloak, the URL of the realm, ie. https://sso.example.com/auth/realms/3scale.
+    // For Auth0, the URL of the tenant, ie. https://example.auth0.com.
+    pub site: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub skip_ssl_certificate_verification: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub automatically_approve_accounts: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub published: Option<bool>,
+}
+
+impl NewAuthenticationProvider {
+    pub fn new<I: Into<String>, S: Into<String>, U: Into<String>>(
+        kind: AuthenticationProviderKind,
+        client_id: I,
+        client_secret: S,
+        site: U,
+    ) -> Self {
+        Self {
+            kind,
+            client_id: client_id.into(),
+            client_secret: client_secret.into(),
+            site: site.into(),
+            name: None,
+            system_name: None,
+            skip_ssl_certificate_verification: None,
+            automatically_approve_accounts: None,
+            published: None,
+        }
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        validate(
+            self.kind,
+            Some(&self.client_id),
+            Some(&self.client_secret),
+            Some(&self.site),
+        )
+    }
+}
+
+impl fmt::Debug for NewAuthenticationProvider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NewAuthenticationProvider")
+            .field("kind", &self.kind)
+            .field("client_id", &self.client_id)
+            .field("client_secret", &Redacted)
+            .field("site", &self.site)
+            .field("name", &self.name)
+            .field("system_name", &self.system_name)
+            .field(
+                "skip_ssl_certificate_verification",
+                &self.skip_ssl_certificate_verification,
+            )
+            .field(
+                "automatically_approve_accounts",
+                &self.automatically_approve_accounts,
+            )
+            .field("published", &self.published)
+            .finish()
+    }
+}
+
+// Parameters to update an authentication provider. The kind of a provider
+// cannot be changed. As with new providers, call `validate` before sending.
+#[derive(Clone, Default, PartialEq, Serialize)]
+pub struct AuthenticationProviderUpdate {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_secret: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub site: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub skip_ssl_certificate_verification: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub automatically_approve_accounts: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub published: Option<bool>,
+}
+
+impl AuthenticationProviderUpdate {
+    // Validates the fields being changed against the kind of the provider.
+    pub fn validate(&self, kind: AuthenticationProviderKind) -> Result<()> {
+        validate(
+            kind,
+            self.client_id.as_deref(),
+            self.client_secret.as_deref(),
+            self.site.as_deref(),
+        )
+    }
+}
+
+impl fmt::Debug for AuthenticationProviderUpdate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AuthenticationProviderUpdate")
+            .field("client_id", &self.client_id)
+            .field(
+                "client_secret",
+                &self.client_secret.as_ref().map(|_| Redacted),
+            )
+            .field("site", &self.site)
+            .field(
+                "skip_ssl_certificate_verification",
+                &self.skip_ssl_certificate_verification,
+            )
+            .field(
+                "automatically_approve_accounts",
+                &self.automatically_approve_accounts,
+            )
+            .field("published", &self.published)
+            .finish()
+    }
+}
+
+// The realm a KeyCloak site URL points to. Both the legacy
+// /auth/realms/{realm} and the current /realms/{realm} paths are accepted.
+pub fn keycloak_realm(site: &str) -> Option<String> {
+    let url = url::Url::parse(site).ok()?;
+    let mut segments = url
+        .path_segments()?
+        .skip_while(|segment| *segment != "realms");
+    segments.next()?;
+    segments
+        .next()
+        .filter(|realm| !realm.is_empty())
+        .map(String::from)
+}
+
+// Checks the settings Porta can't check until the provider is used to sign in.
+// Providers of an unknown kind are rejected, since Porta can't use them.
+fn validate(
+    kind: AuthenticationProviderKind,
+    client_id: Option<&str>,
+    client_secret: Option<&str>,
+    site: Option<&str>,
+) -> Result<()> {
+    let invalid = |reason: String| Err(Error::InvalidAuthenticationProvider(reason));
+
+    if kind == AuthenticationProviderKind::Unknown {
+        return invalid("unknown kind".into());
+    }
+    for (field, value) in [("client_id", client_id), ("client_secret", client_secret)] {
+        if value.is_some_and(|value| value.trim().is_empty()) {
+            return invalid(format!("{} can't be blank", field));
+        }
+    }
+    let site = match site {
+        Some(site) => site,
+        None => return Ok(()),
+    };
+    let url = match url::Url::parse(site) {
+        Ok(url) if matches!(url.scheme(), "http" | "https") && url.has_host() => url,
+        _ => return invalid(format!("site {} is not an http(s) URL", site)),
+    };
+
+    match kind {
+        AuthenticationProviderKind::KeyCloak if keycloak_realm(site).is_none() => {
+            invalid(format!("site {} does not point to a KeyCloak realm", site))
+        }
+        AuthenticationProviderKind::Auth0 if url.scheme() != "https" => {
+            invalid(format!("site {} must use https", site))
+        }
+        AuthenticationProviderKind::Auth0 if !matches!(url.path(), "" | "/") => {
+            invalid(format!("site {} must be the URL of an Auth0 domain", site))
+        }
+        _ => Ok(()),
+    }
+}
+
+// Providers for signing in to the admin portal. The create and update
+// endpoints don't validate their parameters, see `validate`.
 endpoint! { EP_LIST_AUTHN_PROVIDER_ADMIN, GET joining [ "/admin/api/account/authentication_providers.json"] returning AuthenticationProviders }
+endpoint! { EP_READ_AUTHN_PROVIDER_ADMIN, GET joining [ "/admin/api/account/authentication_providers/", ".json" ] returning AuthenticationProviderTag }
+endpoint! { EP_CREATE_AUTHN_PROVIDER_ADMIN, POST joining [ "/admin/api/account/authentication_providers.json" ] returning AuthenticationProviderTag }
+endpoint! { EP_UPDATE_AUTHN_PROVIDER_ADMIN, PUT joining [ "/admin/api/account/authentication_providers/", ".json" ] returning AuthenticationProviderTag }
+endpoint! { EP_DELETE_AUTHN_PROVIDER_ADMIN, DELETE joining [ "/admin/api/account/authentication_providers/", ".json" ] returning () }
+// Providers for developers signing in to the developer portal.
+endpoint! { EP_LIST_AUTHN_PROVIDER_DEVELOPER, GET joining [ "/admin/api/authentication_providers.json"] returning AuthenticationProviders }
+endpoint! { EP_READ_AUTHN_PROVIDER_DEVELOPER, GET joining [ "/admin/api/authentication_providers/", ".json" ] returning AuthenticationProviderTag }
+endpoint! { EP_CREATE_AUTHN_PROVIDER_DEVELOPER, POST joining [ "/admin/api/authentication_providers.json" ] returning AuthenticationProviderTag }
+endpoint! { EP_UPDATE_AUTHN_PROVIDER_DEVELOPER, PUT joining [ "/admin/api/authentication_providers/", ".json" ] returning AuthenticationProviderTag }
+endpoint! { EP_DELETE_AUTHN_PROVIDER_DEVELOPER, DELETE joining [ "/admin/api/authentication_providers/", ".json" ] returning () }
 endpoint_test! { it_parses, EP_LIST_AUTHN_PROVIDER_ADMIN, r##"{
    "authentication_providers" : [
       {
@@ -91,12 +376,122 @@ mod tests {
                 trust_email: false
             },
         ]);
-        let result = serde_json::to_string_pretty(&ap);
-        match result {
-            Err(ref e) => println!("Error: {:#?}", e),
-            _ => (),
+        let json = serde_json::to_value(&ap).unwrap();
+        assert_eq!(
+            json["authentication_providers"][0]["authentication_provider"]["kind"],
+            "keycloak"
+        );
+    }
+
+    #[test]
+    fn it_redacts_client_secrets() {
+        let providers: Vec<AuthenticationProvider> = EP_LIST_AUTHN_PROVIDER_ADMIN
+            .parse_str(RESPONSE)
+            .unwrap()
+            .into();
+        let debug = format!("{:?}", providers[0]);
+        assert!(debug.contains(r#"client_id: "any""#));
+        assert!(debug.contains("client_secret: <redacted>"));
+        assert!(!debug.contains(r#""secret""#));
+
+        let update = AuthenticationProviderUpdate {
+            client_secret: Some("secret".into()),
+            ..Default::default()
+        };
+        assert!(format!("{:?}", update).contains("client_secret: Some(<redacted>)"));
+    }
+
+    #[test]
+    fn it_rejects_unknown_kinds() {
+        let provider = NewAuthenticationProvider::new(
+            AuthenticationProviderKind::Unknown,
+            "id",
+            "secret",
+            "https://example.com",
+        );
+        assert!(matches!(
+            provider.validate(),
+            Err(Error::InvalidAuthenticationProvider(_))
+        ));
+        assert!(serde_json::to_string(&provider).is_err());
+    }
+
+    #[test]
+    fn it_validates_keycloak_settings() {
+        use AuthenticationProviderKind::KeyCloak;
+
+        assert_eq!(
+            keycloak_realm("https://sso.example.com/auth/realms/3scale"),
+            Some("3scale".into())
+        );
+        assert_eq!(
+            keycloak_realm("https://sso.example.com/realms/3scale/"),
+            Some("3scale".into())
+        );
+        assert_eq!(keycloak_realm("https://sso.example.com/auth"), None);
+
+        let provider = NewAuthenticationProvider::new(
+            KeyCloak,
+            "3scale",
+            "secret",
+            "https://sso.example.com/auth/realms/3scale",
+        );
+        assert!(provider.validate().is_ok());
+        let provider = NewAuthenticationProvider {
+            site: "https://sso.example.com".into(),
+            ..provider
+        };
+        assert!(matches!(
+            provider.validate(),
+            Err(Error::InvalidAuthenticationProvider(_))
+        ));
+        let update = AuthenticationProviderUpdate {
+            client_secret: Some(" ".into()),
+            ..Default::default()
+        };
+        assert!(update.validate(KeyCloak).is_err());
+    }
+
+    #[test]
+    fn it_validates_auth0_settings() {
+        use AuthenticationProviderKind::Auth0;
+
+        let valid =
+            NewAuthenticationProvider::new(Auth0, "id", "secret", "https://example.auth0.com");
+        assert!(valid.validate().is_ok());
+        for site in [
+            "http://example.auth0.com",
+            "https://example.auth0.com/authorize",
+            "example.auth0.com",
+        ] {
+            let provider = NewAuthenticationProvider {
+                site: site.into(),
+                ..valid.clone()
+            };
+            assert!(provider.validate().is_err(), "{}", site);
         }
-        assert!(result.is_ok());
-        println!("{}", result.unwrap());
+    }
+
+    #[test]
+    fn it_describes_new_developer_portal_providers() {
+        assert_eq!(
+            EP_CREATE_AUTHN_PROVIDER_DEVELOPER.path(&[]).unwrap(),
+            "/admin/api/authentication_providers.json"
+        );
+        let provider = NewAuthenticationProvider::new(
+            AuthenticationProviderKind::KeyCloak,
+            "any",
+            "secret",
+            "https://secret.com/auth/realms/3scale",
+        );
+        assert_eq!(
+            serde_json::to_value(&provider).unwrap(),
+            serde_json::json!({
+                "kind": "keycloak",
+                "client_id": "any",
+                "client_secret": "secret",
+                "site": "https://secret.com/auth/realms/3scale"
+            })
+        );
     }
 }
@@ -0,0 +1,155 @@
+// Backend APIs hold the private endpoint, metrics and mapping rules that
+// products (services) mount at a given path through backend usages.
+use serde::{Deserialize, Serialize};
+use straitjacket_macro::straitjacket;
+
+pub mod mapping_rule;
+pub mod method;
+pub mod metric;
+pub mod usage;
+
+pub type Metadata = crate::resources::Metadata;
+
+#[straitjacket]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BackendApi {
+    id: u64,
+    name: String,
+    system_name: String,
+    description: Option<String>,
+    private_endpoint: String,
+    account_id: Option<u64>,
+}
+
+impl BackendApi {
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    pub fn system_name(&self) -> &str {
+        self.system_name.as_str()
+    }
+
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
+    pub fn private_endpoint(&self) -> &str {
+        self.private_endpoint.as_str()
+    }
+
+    pub fn account_id(&self) -> Option<u64> {
+        self.account_id
+    }
+}
+
+impl crate::resources::pagination::Paginated for BackendApis {
+    type Item = BackendApi;
+}
+
+// Parameters to create a backend API.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct NewBackendApi {
+    pub name: String,
+    pub private_endpoint: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+impl NewBackendApi {
+    pub fn new<N: Into<String>, E: Into<String>>(name: N, private_endpoint: E) -> Self {
+        Self {
+            name: name.into(),
+            private_endpoint: private_endpoint.into(),
+            ..Default::default()
+        }
+    }
+}
+
+// Parameters to update a backend API.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct BackendApiUpdate {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub private_endpoint: Option<String>,
+}
+
+endpoint! { EP_LIST_BACKEND_APIS, GET joining [ "/admin/api/backend_apis.json" ] returning BackendApis }
+endpoint! { EP_READ_BACKEND_API, GET joining [ "/admin/api/backend_apis/", ".json" ] returning BackendApiTag }
+endpoint! { EP_CREATE_BACKEND_API, POST joining [ "/admin/api/backend_apis.json" ] returning BackendApiTag }
+endpoint! { EP_UPDATE_BACKEND_API, PUT joining [ "/admin/api/backend_apis/", ".json" ] returning BackendApiTag }
+// Fails while the backend is used by any product.
+endpoint! { EP_DELETE_BACKEND_API, DELETE joining [ "/admin/api/backend_apis/", ".json" ] returning () }
+endpoint_test! { it_parses, EP_LIST_BACKEND_APIS, r##"{
+  "backend_apis": [
+    {
+      "backend_api": {
+        "id": 6,
+        "name": "Echo API",
+        "system_name": "echo_api",
+        "description": "The echo backend",
+        "private_endpoint": "https://echo-api.3scale.net:443",
+        "account_id": 2445582571513,
+        "created_at": "2020-06-04T10:37:26+01:00",
+        "updated_at": "2020-06-04T10:37:26+01:00",
+        "links": [
+          {
+            "rel": "metrics",
+            "href": "https://istiodevel-admin.3scale.net/admin/api/backend_apis/6/metrics"
+          },
+          {
+            "rel": "mapping_rules",
+            "href": "https://istiodevel-admin.3scale.net/admin/api/backend_apis/6/mapping_rules"
+          }
+        ]
+      }
+    },
+    {
+      "backend_api": {
+        "id": 7,
+        "name": "Books",
+        "system_name": "books",
+        "description": null,
+        "private_endpoint": "http://books.internal:8080",
+        "account_id": 2445582571513,
+        "created_at": "2020-06-05T10:37:26+01:00",
+        "updated_at": "2020-06-05T10:37:26+01:00"
+      }
+    }
+  ]
+}"## }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_reads_backend_apis() {
+        let backends: Vec<BackendApi> = EP_LIST_BACKEND_APIS.parse_str(RESPONSE).unwrap().into();
+        assert_eq!(backends.len(), 2);
+        assert_eq!(backends[0].system_name(), "echo_api");
+        assert_eq!(
+            backends[0].private_endpoint(),
+            "https://echo-api.3scale.net:443"
+        );
+        assert_eq!(backends[1].description(), None);
+    }
+
+    #[test]
+    fn it_serializes_new_backend_apis() {
+        let backend = NewBackendApi::new("Books", "http://books.internal:8080");
+        assert_eq!(
+            serde_json::to_string(&backend).unwrap(),
+            r#"{"name":"Books","private_endpoint":"http://books.internal:8080"}"#
+        );
+    }
+}
@@ -0,0 +1,37 @@
+// Backend API mapping rules share their representation with the proxy's.
+pub use crate::api::v0::service::proxy::mapping_rules::{
+    MappingRule, MappingRuleAndMetadata, MappingRuleTag, MappingRuleUpdate, MappingRules,
+    NewMappingRule,
+};
+
+endpoint! { EP_LIST_BACKEND_MAPPING_RULES, GET joining [ "/admin/api/backend_apis/", "/mapping_rules.json" ] returning MappingRules }
+endpoint! { EP_CREATE_BACKEND_MAPPING_RULE, POST joining [ "/admin/api/backend_apis/", "/mapping_rules.json" ] returning MappingRuleTag }
+// The endpoints below take the backend API id and the mapping rule id.
+endpoint! { EP_READ_BACKEND_MAPPING_RULE, GET joining [ "/admin/api/backend_apis/", "/mapping_rules/", ".json" ] returning MappingRuleTag }
+endpoint! { EP_UPDATE_BACKEND_MAPPING_RULE, PUT joining [ "/admin/api/backend_apis/", "/mapping_rules/", ".json" ] returning MappingRuleTag }
+endpoint! { EP_DELETE_BACKEND_MAPPING_RULE, DELETE joining [ "/admin/api/backend_apis/", "/mapping_rules/", ".json" ] returning () }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    endpoint_test! { it_parses, EP_LIST_BACKEND_MAPPING_RULES, r##"{
+      "mapping_rules": [
+        {
+          "mapping_rule": {
+            "id": 1,
+            "metric_id": 2555418191876,
+            "pattern": "/books/{id}",
+            "http_method": "GET",
+            "delta": 1,
+            "position": 1,
+            "last": false,
+            "owner_id": 6,
+            "owner_type": "BackendApi",
+            "created_at": "2020-06-04T10:37:26+01:00",
+            "updated_at": "2020-06-04T10:37:26+01:00"
+          }
+        }
+      ]
+    }"## }
+}
@@ -0,0 +1,33 @@
+// Backend API methods share their representation with service methods.
+pub use crate::api::v0::service::method::{
+    Metadata, Method, MethodAndMetadata, MethodTag, MethodUpdate, Methods, NewMethod,
+};
+
+// The endpoints take the backend API id and the id of the parent metric.
+endpoint! { EP_LIST_BACKEND_METHODS, GET joining [ "/admin/api/backend_apis/", "/metrics/", "/methods.json" ] returning Methods }
+endpoint! { EP_CREATE_BACKEND_METHOD, POST joining [ "/admin/api/backend_apis/", "/metrics/", "/methods.json" ] returning MethodTag }
+// Also takes the method id.
+endpoint! { EP_READ_BACKEND_METHOD, GET joining [ "/admin/api/backend_apis/", "/metrics/", "/methods/", ".json" ] returning MethodTag }
+endpoint! { EP_UPDATE_BACKEND_METHOD, PUT joining [ "/admin/api/backend_apis/", "/metrics/", "/methods/", ".json" ] returning MethodTag }
+endpoint! { EP_DELETE_BACKEND_METHOD, DELETE joining [ "/admin/api/backend_apis/", "/metrics/", "/methods/", ".json" ] returning () }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    endpoint_test! { it_parses, EP_LIST_BACKEND_METHODS, r##"{
+      "methods": [
+        {
+          "method": {
+            "id": 2555418191877,
+            "name": "get_book.6",
+            "system_name": "get_book.6",
+            "friendly_name": "Get a book",
+            "parent_id": 2555418191876,
+            "created_at": "2020-06-04T10:37:26+01:00",
+            "updated_at": "2020-06-04T10:37:26+01:00"
+          }
+        }
+      ]
+    }"## }
+}
@@ -0,0 +1,34 @@
+// Backend API metrics share their representation with service metrics.
+pub use crate::api::v0::service::metric::{
+    Metadata, Metric, MetricAndMetadata, MetricHierarchy, MetricTag, MetricUpdate, Metrics,
+    NewMetric,
+};
+
+endpoint! { EP_LIST_BACKEND_METRICS, GET joining [ "/admin/api/backend_apis/", "/metrics.json" ] returning Metrics }
+endpoint! { EP_CREATE_BACKEND_METRIC, POST joining [ "/admin/api/backend_apis/", "/metrics.json" ] returning MetricTag }
+// The endpoints below take the backend API id and the metric id.
+endpoint! { EP_READ_BACKEND_METRIC, GET joining [ "/admin/api/backend_apis/", "/metrics/", ".json" ] returning MetricTag }
+endpoint! { EP_UPDATE_BACKEND_METRIC, PUT joining [ "/admin/api/backend_apis/", "/metrics/", ".json" ] returning MetricTag }
+endpoint! { EP_DELETE_BACKEND_METRIC, DELETE joining [ "/admin/api/backend_apis/", "/metrics/", ".json" ] returning () }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    endpoint_test! { it_parses, EP_LIST_BACKEND_METRICS, r##"{
+      "metrics": [
+        {
+          "metric": {
+            "id": 2555418191876,
+            "name": "hits.6",
+            "system_name": "hits.6",
+            "friendly_name": "Hits",
+            "description": "Number of API hits",
+            "unit": "hit",
+            "created_at": "2020-06-04T10:37:26+01:00",
+            "updated_at": "2020-06-04T10:37:26+01:00"
+          }
+        }
+      ]
+    }"## }
+}
@@ -0,0 +1,119 @@
+// A backend usage mounts a backend API on a product (service) at a path.
+use serde::{Deserialize, Serialize};
+use straitjacket_macro::straitjacket;
+
+pub type Metadata = crate::resources::Metadata;
+
+#[straitjacket]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BackendUsage {
+    id: u64,
+    path: String,
+    service_id: u64,
+    backend_id: u64,
+}
+
+impl BackendUsage {
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    pub fn path(&self) -> &str {
+        self.path.as_str()
+    }
+
+    pub fn service_id(&self) -> u64 {
+        self.service_id
+    }
+
+    pub fn backend_id(&self) -> u64 {
+        self.backend_id
+    }
+}
+
+// Parameters to mount a backend API on a product.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct NewBackendUsage {
+    pub backend_api_id: u64,
+    pub path: String,
+}
+
+impl NewBackendUsage {
+    pub fn new<S: Into<String>>(backend_api_id: u64, path: S) -> Self {
+        Self {
+            backend_api_id,
+            path: path.into(),
+        }
+    }
+}
+
+// Moves a backend API to another path of the product.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BackendUsageUpdate {
+    pub path: String,
+}
+
+// Porta lists usages as a bare array rather than under a "backend_usages" key.
+endpoint! { EP_LIST_BACKEND_USAGES, GET joining [ "/admin/api/services/", "/backend_usages.json" ] returning Vec<BackendUsageTag> }
+endpoint! { EP_CREATE_BACKEND_USAGE, POST joining [ "/admin/api/services/", "/backend_usages.json" ] returning BackendUsageTag }
+// The endpoints below take the service id and the backend usage id.
+endpoint! { EP_READ_BACKEND_USAGE, GET joining [ "/admin/api/services/", "/backend_usages/", ".json" ] returning BackendUsageTag }
+endpoint! { EP_UPDATE_BACKEND_USAGE, PUT joining [ "/admin/api/services/", "/backend_usages/", ".json" ] returning BackendUsageTag }
+endpoint! { EP_DELETE_BACKEND_USAGE, DELETE joining [ "/admin/api/services/", "/backend_usages/", ".json" ] returning () }
+endpoint_test! { it_parses, EP_LIST_BACKEND_USAGES, r##"[
+  {
+    "backend_usage": {
+      "id": 8,
+      "path": "/",
+      "service_id": 2555417764324,
+      "backend_id": 6,
+      "links": [
+        {
+          "rel": "service",
+          "href": "https://istiodevel-admin.3scale.net/admin/api/services/2555417764324"
+        },
+        {
+          "rel": "backend_api",
+          "href": "https://istiodevel-admin.3scale.net/admin/api/backend_apis/6"
+        }
+      ]
+    }
+  },
+  {
+    "backend_usage": {
+      "id": 9,
+      "path": "/books",
+      "service_id": 2555417764324,
+      "backend_id": 7
+    }
+  }
+]"## }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_reads_mount_paths() {
+        let usages = EP_LIST_BACKEND_USAGES
+            .parse_str(RESPONSE)
+            .unwrap()
+            .into_iter()
+            .map(BackendUsage::from)
+            .collect::<Vec<_>>();
+        assert_eq!(usages[1].path(), "/books");
+        assert_eq!(usages[1].backend_id(), 7);
+    }
+
+    #[test]
+    fn it_describes_new_backend_usages() {
+        assert_eq!(
+            EP_CREATE_BACKEND_USAGE.path(&["3"]).unwrap(),
+            "/admin/api/services/3/backend_usages.json"
+        );
+        assert_eq!(
+            serde_json::to_string(&NewBackendUsage::new(6, "/v2")).unwrap(),
+            r#"{"backend_api_id":6,"path":"/v2"}"#
+        );
+    }
+}
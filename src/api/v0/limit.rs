@@ -1,9 +1,14 @@
 use serde::{Deserialize, Serialize};
 use straitjacket_macro::straitjacket;
 
+use crate::error::{Error, Result};
+
+#[cfg(any(feature = "client", feature = "async-client"))]
+use crate::client::PlannedRequest;
+
 pub type Metadata = crate::resources::Metadata;
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum Period {
     Minute,
@@ -27,7 +32,192 @@ pub struct Limit {
     value: u64,
 }
 
+impl Limit {
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    pub fn metric_id(&self) -> u64 {
+        self.metric_id
+    }
+
+    pub fn plan_id(&self) -> u64 {
+        self.plan_id
+    }
+
+    pub fn period(&self) -> Period {
+        self.period
+    }
+
+    pub fn value(&self) -> u64 {
+        self.value
+    }
+}
+
+// Parameters to create a limit.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct NewLimit {
+    pub period: Period,
+    pub value: u64,
+}
+
+// Parameters to update a limit.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct LimitUpdate {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub period: Option<Period>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<u64>,
+}
+
 endpoint! { EP_LIST_LIMITS, GET joining [ "/admin/api/application_plans/", "/limits.json"] returning Limits }
+// These take the application plan and metric ids.
+endpoint! { EP_LIST_METRIC_LIMITS, GET joining [ "/admin/api/application_plans/", "/metrics/", "/limits.json" ] returning Limits }
+endpoint! { EP_CREATE_LIMIT, POST joining [ "/admin/api/application_plans/", "/metrics/", "/limits.json" ] returning LimitTag }
+// These take the application plan, metric and limit ids.
+endpoint! { EP_READ_LIMIT, GET joining [ "/admin/api/application_plans/", "/metrics/", "/limits/", ".json" ] returning LimitTag }
+endpoint! { EP_UPDATE_LIMIT, PUT joining [ "/admin/api/application_plans/", "/metrics/", "/limits/", ".json" ] returning LimitTag }
+endpoint! { EP_DELETE_LIMIT, DELETE joining [ "/admin/api/application_plans/", "/metrics/", "/limits/", ".json" ] returning () }
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum LimitChange {
+    Create {
+        metric_id: u64,
+        limit: NewLimit,
+    },
+    Update {
+        metric_id: u64,
+        id: u64,
+        update: LimitUpdate,
+    },
+    Delete {
+        metric_id: u64,
+        id: u64,
+    },
+}
+
+// The changes that set the limits of an application plan to a desired list,
+// given as (metric id, period, value).
+//
+// Porta allows a single limit per metric and period, so that is what limits
+// are matched by. Repeated entries are collapsed, but entries with different
+// values for the same metric and period are an error. Existing limits that
+// match nothing are deleted.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LimitsPlan {
+    changes: Vec<LimitChange>,
+}
+
+impl LimitsPlan {
+    pub fn new(current: &[Limit], desired: &[(u64, Period, u64)]) -> Result<Self> {
+        let mut taken = vec![false; current.len()];
+        let mut changes = Vec::new();
+        let mut seen: Vec<(u64, Period, u64)> = Vec::with_capacity(desired.len());
+
+        for &(metric_id, period, value) in desired.iter() {
+            match seen
+                .iter()
+                .find(|(m, p, _)| *m == metric_id && *p == period)
+            {
+                Some(&(_, _, seen_value)) if seen_value == value => continue,
+                Some(_) => return Err(Error::ConflictingLimits { metric_id, period }),
+                None => seen.push((metric_id, period, value)),
+            }
+            let existing = current
+                .iter()
+                .enumerate()
+                .find(|(i, l)| !taken[*i] && l.metric_id == metric_id && l.period == period);
+            match existing {
+                Some((i, limit)) => {
+                    taken[i] = true;
+                    if limit.value != value {
+                        changes.push(LimitChange::Update {
+                            metric_id,
+                            id: limit.id,
+                            update: LimitUpdate {
+                                value: Some(value),
+                                ..Default::default()
+                            },
+                        });
+                    }
+                }
+                None => changes.push(LimitChange::Create {
+                    metric_id,
+                    limit: NewLimit { period, value },
+                }),
+            }
+        }
+
+        // Delete first, so that freed metric and period pairs can be reused.
+        let deletes = current
+            .iter()
+            .zip(taken)
+            .filter(|(_, taken)| !taken)
+            .map(|(limit, _)| LimitChange::Delete {
+                metric_id: limit.metric_id,
+                id: limit.id,
+            });
+        Ok(Self {
+            changes: deletes.chain(changes).collect(),
+        })
+    }
+
+    pub fn changes(&self) -> &[LimitChange] {
+        self.changes.as_slice()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    // Apply the changes in order to the given application plan, stopping at
+    // the first failure.
+    #[cfg(feature = "client")]
+    pub fn apply(&self, client: &crate::client::Client, plan_id: &str) -> Result<()> {
+        client.send_planned(&self.requests(plan_id)?)
+    }
+
+    #[cfg(feature = "async-client")]
+    pub async fn apply_async(
+        &self,
+        client: &crate::client::AsyncClient,
+        plan_id: &str,
+    ) -> Result<()> {
+        client.send_planned(&self.requests(plan_id)?).await
+    }
+
+    #[cfg(any(feature = "client", feature = "async-client"))]
+    fn requests(&self, plan_id: &str) -> Result<Vec<PlannedRequest>> {
+        self.changes
+            .iter()
+            .map(|change| match change {
+                LimitChange::Create { metric_id, limit } => PlannedRequest::new(
+                    &EP_CREATE_LIMIT,
+                    &[plan_id, &metric_id.to_string()],
+                    None::<&()>,
+                    Some(limit),
+                ),
+                LimitChange::Update {
+                    metric_id,
+                    id,
+                    update,
+                } => PlannedRequest::new(
+                    &EP_UPDATE_LIMIT,
+                    &[plan_id, &metric_id.to_string(), &id.to_string()],
+                    None::<&()>,
+                    Some(update),
+                ),
+                LimitChange::Delete { metric_id, id } => PlannedRequest::new(
+                    &EP_DELETE_LIMIT,
+                    &[plan_id, &metric_id.to_string(), &id.to_string()],
+                    None::<&()>,
+                    None::<&()>,
+                ),
+            })
+            .collect()
+    }
+}
+
 endpoint_test! { it_parses, EP_LIST_LIMITS, r##"{
    "limits":[
       {
@@ -72,11 +262,110 @@ mod tests {
             value: 5,
         }]);
         let result = serde_json::to_string_pretty(&limits);
-        match result {
-            Err(ref e) => println!("Error: {:#?}", e),
-            _ => (),
+        if let Err(ref e) = result {
+            println!("Error: {:#?}", e);
         }
         assert!(result.is_ok());
         println!("{}", result.unwrap());
     }
+
+    #[test]
+    fn it_builds_metric_limit_paths() {
+        assert_eq!(
+            EP_UPDATE_LIMIT.path(&["1", "2", "3"]).unwrap(),
+            "/admin/api/application_plans/1/metrics/2/limits/3.json"
+        );
+        assert_eq!(
+            EP_LIST_METRIC_LIMITS.path(&["1", "2"]).unwrap(),
+            "/admin/api/application_plans/1/metrics/2/limits.json"
+        );
+    }
+
+    #[test]
+    fn it_reconciles_limits() {
+        let limit = |id, metric_id, period, value| Limit {
+            id,
+            metric_id,
+            plan_id: 1,
+            period,
+            value,
+        };
+        let current = vec![
+            limit(10, 100, Period::Day, 5),
+            limit(11, 100, Period::Month, 50),
+            limit(12, 200, Period::Day, 5),
+        ];
+        let desired = vec![
+            (100, Period::Day, 5),
+            (100, Period::Month, 100),
+            (300, Period::Hour, 1),
+        ];
+
+        let changes = LimitsPlan::new(&current, &desired).unwrap();
+        assert_eq!(
+            changes.changes(),
+            &[
+                LimitChange::Delete {
+                    metric_id: 200,
+                    id: 12
+                },
+                LimitChange::Update {
+                    metric_id: 100,
+                    id: 11,
+                    update: LimitUpdate {
+                        value: Some(100),
+                        ..Default::default()
+                    }
+                },
+                LimitChange::Create {
+                    metric_id: 300,
+                    limit: NewLimit {
+                        period: Period::Hour,
+                        value: 1
+                    }
+                },
+            ]
+        );
+        assert!(LimitsPlan::new(
+            &current,
+            &[
+                (100, Period::Day, 5),
+                (100, Period::Month, 50),
+                (200, Period::Day, 5),
+            ]
+        )
+        .unwrap()
+        .is_empty());
+    }
+
+    #[test]
+    fn it_collapses_repeated_entries() {
+        let desired = vec![(300, Period::Hour, 1), (300, Period::Hour, 1)];
+        assert_eq!(
+            LimitsPlan::new(&[], &desired).unwrap().changes(),
+            &[LimitChange::Create {
+                metric_id: 300,
+                limit: NewLimit {
+                    period: Period::Hour,
+                    value: 1
+                }
+            }]
+        );
+    }
+
+    #[test]
+    fn it_rejects_conflicting_entries() {
+        let desired = vec![
+            (300, Period::Hour, 1),
+            (300, Period::Day, 2),
+            (300, Period::Hour, 2),
+        ];
+        assert!(matches!(
+            LimitsPlan::new(&[], &desired),
+            Err(Error::ConflictingLimits {
+                metric_id: 300,
+                period: Period::Hour
+            })
+        ));
+    }
 }
@@ -1,6 +1,13 @@
+//! Resources of version 0 of Porta's admin API.
+//!
+//! Updates take `*Update` structs whose fields are all optional: fields left
+//! as `None` are not sent, so Porta doesn't change them.
+
 pub mod account;
 pub mod api_doc;
 pub mod application;
 pub mod authentication_provider;
+pub mod backend_api;
 pub mod limit;
+pub mod pricing_rule;
 pub mod service;
@@ -0,0 +1,256 @@
+use serde::{Deserialize, Deserializer, Serialize};
+use straitjacket_macro::straitjacket;
+
+pub type Metadata = crate::resources::Metadata;
+
+// Costs are computed in integer ten-thousandths of the currency unit, the
+// precision Porta stores costs per unit with, so that adding them up doesn't
+// accumulate floating point errors.
+pub const COST_SCALE: u64 = 10_000;
+
+// Porta renders decimal amounts as strings.
+fn parse_decimal<'de, D: Deserializer<'de>>(deserializer: D) -> Result<f64, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Decimal {
+        Number(f64),
+        String(String),
+    }
+
+    match Decimal::deserialize(deserializer)? {
+        Decimal::Number(n) => Ok(n),
+        Decimal::String(s) => s.trim().parse().map_err(serde::de::Error::custom),
+    }
+}
+
+// Scale a cost by `COST_SCALE` working on its shortest decimal form, which is
+// what Porta rendered, rather than on its binary value: 0.00015 is stored as
+// slightly less than that, but should still round up to 2.
+fn scale_cost(cost: f64) -> u64 {
+    if cost.is_nan() || cost <= 0.0 {
+        return 0;
+    }
+    let decimal = cost.to_string();
+    let (integer, fraction) = decimal.split_once('.').unwrap_or((&decimal, ""));
+    let fraction = fraction.as_bytes();
+    let mut scaled = match integer.parse::<u128>() {
+        Ok(integer) => integer,
+        Err(_) => return u64::MAX,
+    };
+    for i in 0..4 {
+        let digit = fraction.get(i).map_or(0, |digit| digit - b'0');
+        scaled = scaled.saturating_mul(10).saturating_add(u128::from(digit));
+    }
+    if fraction.get(4).is_some_and(|digit| *digit >= b'5') {
+        scaled = scaled.saturating_add(1);
+    }
+    scaled.min(u128::from(u64::MAX)) as u64
+}
+
+#[straitjacket]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PricingRule {
+    id: u64,
+    metric_id: u64,
+    plan_id: Option<u64>,
+    #[serde(deserialize_with = "parse_decimal")]
+    cost_per_unit: f64,
+    min: u64,
+    // no upper bound if missing
+    max: Option<u64>,
+}
+
+impl PricingRule {
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    pub fn metric_id(&self) -> u64 {
+        self.metric_id
+    }
+
+    pub fn plan_id(&self) -> Option<u64> {
+        self.plan_id
+    }
+
+    pub fn cost_per_unit(&self) -> f64 {
+        self.cost_per_unit
+    }
+
+    pub fn min(&self) -> u64 {
+        self.min
+    }
+
+    pub fn max(&self) -> Option<u64> {
+        self.max
+    }
+
+    // How many units out of a usage count fall within the rule's range. Both
+    // ends of the range are inclusive.
+    pub fn units(&self, usage: u64) -> u64 {
+        if usage < self.min {
+            return 0;
+        }
+        let (lower, upper) = (
+            self.min.max(1),
+            self.max.map_or(usage, |max| max.min(usage)),
+        );
+        if upper < lower {
+            return 0;
+        }
+        upper - lower + 1
+    }
+
+    // The cost per unit in ten-thousandths, rounded half up from its decimal
+    // form. Negative costs count as free.
+    pub fn scaled_cost_per_unit(&self) -> u64 {
+        scale_cost(self.cost_per_unit)
+    }
+
+    // The cost of a usage count in ten-thousandths of the currency unit.
+    pub fn cost(&self, usage: u64) -> u128 {
+        u128::from(self.units(usage)) * u128::from(self.scaled_cost_per_unit())
+    }
+}
+
+// Parameters to create a pricing rule.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct NewPricingRule {
+    pub min: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max: Option<u64>,
+    pub cost_per_unit: f64,
+}
+
+// Variable cost of a metric's usage under a plan's pricing rules, as Porta
+// would bill it: each unit is charged at the cost of the rule whose range it
+// falls in. The cost is exact, in ten-thousandths of the currency unit (see
+// `COST_SCALE`), with only the cost per unit of each rule being rounded.
+pub fn variable_cost(rules: &[PricingRule], metric_id: u64, usage: u64) -> u128 {
+    rules
+        .iter()
+        .filter(|rule| rule.metric_id == metric_id)
+        .map(|rule| rule.cost(usage))
+        .sum()
+}
+
+// Variable cost of the usage of several metrics, given as (metric id, usage).
+pub fn total_variable_cost(rules: &[PricingRule], usages: &[(u64, u64)]) -> u128 {
+    usages
+        .iter()
+        .map(|&(metric_id, usage)| variable_cost(rules, metric_id, usage))
+        .sum()
+}
+
+endpoint! { EP_LIST_PLAN_PRICING_RULES, GET joining [ "/admin/api/application_plans/", "/pricing_rules.json" ] returning PricingRules }
+// These take the application plan and metric ids.
+endpoint! { EP_LIST_PRICING_RULES, GET joining [ "/admin/api/application_plans/", "/metrics/", "/pricing_rules.json" ] returning PricingRules }
+endpoint! { EP_CREATE_PRICING_RULE, POST joining [ "/admin/api/application_plans/", "/metrics/", "/pricing_rules.json" ] returning PricingRuleTag }
+// This takes the application plan, metric and pricing rule ids.
+endpoint! { EP_DELETE_PRICING_RULE, DELETE joining [ "/admin/api/application_plans/", "/metrics/", "/pricing_rules/", ".json" ] returning () }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    endpoint_test! { it_parses, EP_LIST_PLAN_PRICING_RULES, r##"{
+      "pricing_rules": [
+        {
+          "pricing_rule": {
+            "id": 2639696107090,
+            "metric_id": 2555418191879,
+            "cost_per_unit": "0.5",
+            "min": 1,
+            "max": 100,
+            "plan_id": 2357356012630,
+            "created_at": "2019-03-19T09:04:54+00:00",
+            "updated_at": "2019-03-19T09:04:54+00:00",
+            "links": [
+              {
+                "rel": "metric",
+                "href": "https://istiodevel-admin.3scale.net/admin/api/services/2555417777820/metrics/2555418191879"
+              },
+              {
+                "rel": "plan",
+                "href": "https://istiodevel-admin.3scale.net/admin/api/services/2555417777820/application_plans/2357356012630"
+              }
+            ]
+          }
+        },
+        {
+          "pricing_rule": {
+            "id": 2639696107091,
+            "metric_id": 2555418191879,
+            "cost_per_unit": 0.1,
+            "min": 101,
+            "max": null,
+            "plan_id": 2357356012630,
+            "created_at": "2019-03-19T09:04:54+00:00",
+            "updated_at": "2019-03-19T09:04:54+00:00"
+          }
+        }
+      ]
+    }"## }
+
+    #[test]
+    fn it_computes_variable_costs() {
+        let rules: Vec<PricingRule> = EP_LIST_PLAN_PRICING_RULES
+            .parse_str(RESPONSE)
+            .unwrap()
+            .into();
+        assert_eq!(rules[0].cost_per_unit(), 0.5);
+
+        let metric_id = 2555418191879;
+        assert_eq!(variable_cost(&rules, metric_id, 0), 0);
+        assert_eq!(variable_cost(&rules, metric_id, 10), 5 * 10_000);
+        assert_eq!(variable_cost(&rules, metric_id, 100), 50 * 10_000);
+        assert_eq!(variable_cost(&rules, metric_id, 150), 55 * 10_000);
+        assert_eq!(variable_cost(&rules, 1, 150), 0);
+        assert_eq!(
+            total_variable_cost(&rules, &[(metric_id, 150), (1, 10)]),
+            55 * 10_000
+        );
+    }
+
+    #[test]
+    fn it_adds_up_costs_without_rounding_errors() {
+        let rules = (1..=10)
+            .map(|n| {
+                serde_json::from_str(&format!(
+                    r#"{{ "id": {n}, "metric_id": 1, "cost_per_unit": "0.1", "min": {n}, "max": {n} }}"#,
+                    n = n
+                ))
+                .unwrap()
+            })
+            .collect::<Vec<PricingRule>>();
+        assert_eq!(variable_cost(&rules, 1, 10), 10_000);
+
+        let rule: PricingRule = serde_json::from_str(
+            r#"{ "id": 1, "metric_id": 1, "cost_per_unit": "0.00015", "min": 1 }"#,
+        )
+        .unwrap();
+        assert_eq!(rule.scaled_cost_per_unit(), 2);
+    }
+
+    #[test]
+    fn it_counts_units_within_inclusive_ranges() {
+        let rules: Vec<PricingRule> = EP_LIST_PLAN_PRICING_RULES
+            .parse_str(RESPONSE)
+            .unwrap()
+            .into();
+        assert_eq!(rules[0].units(100), 100);
+        assert_eq!(rules[0].units(500), 100);
+        assert_eq!(rules[1].units(100), 0);
+        assert_eq!(rules[1].units(101), 1);
+    }
+
+    #[test]
+    fn it_counts_units_up_to_the_maximum_usage() {
+        let rule: PricingRule = serde_json::from_str(
+            r#"{ "id": 1, "metric_id": 2, "cost_per_unit": "0.5", "min": 1 }"#,
+        )
+        .unwrap();
+        assert_eq!(rule.units(u64::MAX), u64::MAX);
+        assert_eq!(rule.units(0), 0);
+    }
+}
@@ -1,10 +1,12 @@
 use serde::{Deserialize, Serialize};
 use straitjacket_macro::straitjacket;
 
+pub mod feature;
 pub mod method;
 pub mod metric;
 pub mod plan;
 pub mod proxy;
+pub mod service_plan;
 
 #[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -67,6 +69,14 @@ impl Service {
         self.id
     }
 
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    pub fn state(&self) -> &str {
+        self.state.as_str()
+    }
+
     pub fn system_name(&self) -> &str {
         self.system_name.as_str()
     }
@@ -83,19 +93,23 @@ impl Service {
         self.deployment_option
     }
 
+    pub fn support_email(&self) -> Option<&str> {
+        self.support_email.as_deref()
+    }
+
     pub fn metadata(&self) -> Option<&crate::resources::Metadata> {
         self.metadata.as_ref()
     }
 
-    pub fn metrics(&self) -> Result<crate::deps::url::Url, Box<dyn std::error::Error>> {
+    pub fn metrics(&self) -> crate::error::Result<crate::deps::url::Url> {
         self.metadata()
-            .ok_or("no metadata present")?
+            .ok_or(crate::Error::MissingMetadata)?
             .find_url("metrics")
     }
 
-    pub fn application_plans(&self) -> Result<crate::deps::url::Url, Box<dyn std::error::Error>> {
+    pub fn application_plans(&self) -> crate::error::Result<crate::deps::url::Url> {
         self.metadata()
-            .ok_or("no metadata present")?
+            .ok_or(crate::Error::MissingMetadata)?
             .find_url("application_plans")
     }
 }
@@ -114,7 +128,67 @@ impl std::fmt::Display for Service {
     }
 }
 
+// Parameters to create a service. Only the name is required.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct NewService {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deployment_option: Option<DeploymentOption>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backend_version: Option<AuthenticationMode>,
+}
+
+impl NewService {
+    pub fn new<S: Into<String>>(name: S) -> Self {
+        Self {
+            name: name.into(),
+            ..Default::default()
+        }
+    }
+}
+
+// Parameters to update a service.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ServiceUpdate {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub support_email: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deployment_option: Option<DeploymentOption>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backend_version: Option<AuthenticationMode>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub intentions_required: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub buyers_manage_apps: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub buyers_manage_keys: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub referrer_filters_required: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub custom_keys_enabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub buyer_key_regenerate_enabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mandatory_app_key: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub buyer_can_select_plan: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub buyer_plan_change_permission: Option<String>,
+}
+
 endpoint!(LIST, GET joining [ "/admin/api/services.json"] returning Services);
+endpoint! { READ, GET joining [ "/admin/api/services/", ".json" ] returning ServiceTag }
+endpoint! { CREATE, POST joining [ "/admin/api/services.json" ] returning ServiceTag }
+endpoint! { UPDATE, PUT joining [ "/admin/api/services/", ".json" ] returning ServiceTag }
+endpoint! { DELETE, DELETE joining [ "/admin/api/services/", ".json" ] returning () }
 
 #[cfg(test)]
 mod test {
@@ -432,4 +506,86 @@ mod test {
             .iter()
             .all(|svc| svc.application_plans().is_ok()));
     }
+
+    mod read {
+        use super::*;
+
+        endpoint_test! { it_parses, READ, r##"{
+          "service": {
+            "id": 2555417777820,
+            "name": "echo-api",
+            "state": "incomplete",
+            "system_name": "echo-api",
+            "backend_version": "1",
+            "deployment_option": "self_managed",
+            "support_email": "joaquim@redhat.com",
+            "description": "Echo API",
+            "intentions_required": false,
+            "buyers_manage_apps": true,
+            "buyers_manage_keys": true,
+            "referrer_filters_required": false,
+            "custom_keys_enabled": true,
+            "buyer_key_regenerate_enabled": true,
+            "mandatory_app_key": true,
+            "buyer_can_select_plan": false,
+            "buyer_plan_change_permission": "request",
+            "created_at": "2019-03-19T09:01:01+00:00",
+            "updated_at": "2020-04-05T22:44:57+01:00",
+            "links": [
+              {
+                "rel": "metrics",
+                "href": "https://istiodevel-admin.3scale.net/admin/api/services/2555417777820/metrics"
+              },
+              {
+                "rel": "self",
+                "href": "https://istiodevel-admin.3scale.net/admin/api/services/2555417777820"
+              }
+            ]
+          }
+        }"## }
+
+        #[test]
+        fn it_reads_a_single_service() {
+            let service: Service = READ.parse_str(RESPONSE).unwrap().into();
+            assert_eq!(service.id(), 2555417777820);
+            assert_eq!(service.support_email(), Some("joaquim@redhat.com"));
+            assert!(service.metrics().is_ok());
+        }
+
+        #[test]
+        fn it_builds_paths_with_the_service_id() {
+            assert_eq!(
+                READ.path(&["2555417777820"]).unwrap(),
+                "/admin/api/services/2555417777820.json"
+            );
+            assert_eq!(DELETE.method(), &http::Method::DELETE);
+            assert_eq!(UPDATE.method(), &http::Method::PUT);
+        }
+    }
+
+    #[test]
+    fn it_serializes_only_the_provided_parameters() {
+        let new_service = NewService {
+            deployment_option: Some(DeploymentOption::SelfManaged),
+            backend_version: Some(AuthenticationMode::OIDC),
+            ..NewService::new("echo-api")
+        };
+        assert_eq!(
+            serde_json::to_value(&new_service).unwrap(),
+            serde_json::json!({
+                "name": "echo-api",
+                "deployment_option": "self_managed",
+                "backend_version": "oidc",
+            })
+        );
+
+        let update = ServiceUpdate {
+            description: Some("Echo API v2".into()),
+            ..Default::default()
+        };
+        assert_eq!(
+            serde_json::to_value(&update).unwrap(),
+            serde_json::json!({ "description": "Echo API v2" })
+        );
+    }
 }
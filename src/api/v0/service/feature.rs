@@ -0,0 +1,52 @@
+// Features scoped to a service, which can be enabled on its application and
+// service plans.
+pub use crate::api::v0::account::feature::{
+    Feature, FeatureAndMetadata, FeatureTag, FeatureUpdate, Features, Metadata, NewFeature, Scope,
+};
+
+endpoint! { LIST, GET joining [ "/admin/api/services/", "/features.json" ] returning Features }
+endpoint! { READ, GET joining [ "/admin/api/services/", "/features/", ".json" ] returning FeatureTag }
+endpoint! { CREATE, POST joining [ "/admin/api/services/", "/features.json" ] returning FeatureTag }
+endpoint! { UPDATE, PUT joining [ "/admin/api/services/", "/features/", ".json" ] returning FeatureTag }
+endpoint! { DELETE, DELETE joining [ "/admin/api/services/", "/features/", ".json" ] returning () }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    endpoint_test! { it_parses, LIST, r##"{
+      "features": [
+        {
+          "feature": {
+            "id": 4142040,
+            "name": "unlimited_greetings",
+            "system_name": "unlimited_greetings",
+            "account_id": 2445582571513,
+            "scope": "application_plan",
+            "visible": true,
+            "description": "Say hello as much as you want",
+            "created_at": "2020-05-04T19:03:52+01:00",
+            "updated_at": "2020-05-04T19:03:52+01:00"
+          }
+        },
+        {
+          "feature": {
+            "id": 4142041,
+            "name": "premium_support",
+            "system_name": "premium_support",
+            "scope": "service_plan",
+            "visible": false,
+            "created_at": "2020-05-04T19:03:52+01:00",
+            "updated_at": "2020-05-04T19:03:52+01:00"
+          }
+        }
+      ]
+    }"## }
+
+    #[test]
+    fn it_parses_service_scopes() {
+        let features: Vec<Feature> = LIST.parse_str(RESPONSE).unwrap().into();
+        let scopes = features.iter().map(Feature::scope).collect::<Vec<_>>();
+        assert_eq!(scopes, vec![Scope::ApplicationPlan, Scope::ServicePlan]);
+    }
+}
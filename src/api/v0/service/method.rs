@@ -8,11 +8,140 @@ pub struct Metadata;
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 // A service method
 pub struct Method {
-    id: String,
+    id: u64,
     name: String,
     system_name: String,
     friendly_name: String,
-    service_id: String, // a service id
-    description: String,
-    metric_id: String,
+    service_id: Option<u64>,
+    description: Option<String>,
+    unit: Option<String>,
+    // See the notes in Metric: XML output uses metric_id, JSON uses parent_id.
+    metric_id: Option<u64>,
+    parent_id: Option<u64>,
+}
+
+impl Method {
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    pub fn system_name(&self) -> &str {
+        self.system_name.as_str()
+    }
+
+    pub fn friendly_name(&self) -> &str {
+        self.friendly_name.as_str()
+    }
+
+    pub fn service_id(&self) -> Option<u64> {
+        self.service_id
+    }
+
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
+    pub fn unit(&self) -> Option<&str> {
+        self.unit.as_deref()
+    }
+
+    // The metric this method belongs to.
+    pub fn metric_id(&self) -> Option<u64> {
+        self.parent_id.or(self.metric_id)
+    }
+}
+
+// Parameters to create a method.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct NewMethod {
+    pub friendly_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unit: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+impl NewMethod {
+    pub fn new<F: Into<String>>(friendly_name: F) -> Self {
+        Self {
+            friendly_name: friendly_name.into(),
+            ..Default::default()
+        }
+    }
+}
+
+// Parameters to update a method.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct MethodUpdate {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub friendly_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unit: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+endpoint! { LIST, GET joining [ "/admin/api/services/", "/metrics/", "/methods.json" ] returning Methods }
+endpoint! { READ, GET joining [ "/admin/api/services/", "/metrics/", "/methods/", ".json" ] returning MethodTag }
+endpoint! { CREATE, POST joining [ "/admin/api/services/", "/metrics/", "/methods.json" ] returning MethodTag }
+endpoint! { UPDATE, PUT joining [ "/admin/api/services/", "/metrics/", "/methods/", ".json" ] returning MethodTag }
+endpoint! { DELETE, DELETE joining [ "/admin/api/services/", "/metrics/", "/methods/", ".json" ] returning () }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    endpoint_test! { it_parses, LIST, r#"{
+      "methods": [
+        {
+          "method": {
+            "id": 2555418191879,
+            "name": "get_slash",
+            "system_name": "get_slash",
+            "friendly_name": "get-slash",
+            "service_id": 2555417777820,
+            "description": "GET /",
+            "parent_id": 2555418191876,
+            "created_at": "2019-03-19T09:04:31+00:00",
+            "updated_at": "2019-03-19T09:04:31+00:00",
+            "links": [
+              {
+                "rel": "parent",
+                "href": "https://istiodevel-admin.3scale.net/admin/api/services/2555417777820/metrics/2555418191876"
+              },
+              {
+                "rel": "self",
+                "href": "https://istiodevel-admin.3scale.net/admin/api/services/2555417777820/metrics/2555418191876/methods/2555418191879"
+              }
+            ]
+          }
+        }
+      ]
+    }"# }
+
+    #[test]
+    fn it_uses_numeric_ids() {
+        let methods: Vec<Method> = LIST.parse_str(RESPONSE).unwrap().into();
+        assert_eq!(methods[0].id(), 2555418191879);
+        assert_eq!(methods[0].service_id(), Some(2555417777820));
+        assert_eq!(methods[0].metric_id(), Some(2555418191876));
+    }
+
+    #[test]
+    fn it_builds_paths_with_service_metric_and_method_ids() {
+        assert_eq!(
+            UPDATE.path(&["1", "2", "3"]).unwrap(),
+            "/admin/api/services/1/metrics/2/methods/3.json"
+        );
+        assert_eq!(
+            CREATE.path(&["1", "2"]).unwrap(),
+            "/admin/api/services/1/metrics/2/methods.json"
+        );
+    }
 }
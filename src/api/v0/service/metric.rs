@@ -1,6 +1,10 @@
 use serde::{Deserialize, Serialize};
 use straitjacket_macro::straitjacket;
 
+pub mod hierarchy;
+
+pub use hierarchy::MetricHierarchy;
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Metadata;
 
@@ -37,6 +41,10 @@ impl Metric {
         self.friendly_name.as_str()
     }
 
+    pub fn description(&self) -> &str {
+        self.description.as_str()
+    }
+
     pub fn unit(&self) -> &str {
         self.unit.as_str()
     }
@@ -46,7 +54,43 @@ impl Metric {
     }
 }
 
+// Parameters to create a metric.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct NewMetric {
+    pub friendly_name: String,
+    pub unit: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+impl NewMetric {
+    pub fn new<F: Into<String>, U: Into<String>>(friendly_name: F, unit: U) -> Self {
+        Self {
+            friendly_name: friendly_name.into(),
+            unit: unit.into(),
+            ..Default::default()
+        }
+    }
+}
+
+// Parameters to update a metric.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct MetricUpdate {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub friendly_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unit: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
 endpoint! { LIST, GET joining [ "/admin/api/services/", "/metrics.json"] returning Metrics }
+endpoint! { READ, GET joining [ "/admin/api/services/", "/metrics/", ".json" ] returning MetricTag }
+endpoint! { CREATE, POST joining [ "/admin/api/services/", "/metrics.json" ] returning MetricTag }
+endpoint! { UPDATE, PUT joining [ "/admin/api/services/", "/metrics/", ".json" ] returning MetricTag }
+endpoint! { DELETE, DELETE joining [ "/admin/api/services/", "/metrics/", ".json" ] returning () }
 
 #[cfg(test)]
 mod test {
@@ -145,4 +189,26 @@ mod test {
         }
       ]
     }"# }
+
+    #[test]
+    fn it_builds_the_hierarchy_from_the_list() {
+        let metrics: Vec<Metric> = LIST.parse_str(RESPONSE).unwrap().into();
+        let hierarchy = MetricHierarchy::new(&metrics);
+        assert!(hierarchy.is_valid());
+        let hits = hierarchy.hits().unwrap();
+        assert_eq!(hits.methods().len(), 1);
+        assert_eq!(hits.methods()[0].system_name(), "metamethod");
+    }
+
+    #[test]
+    fn it_serializes_only_the_fields_to_update() {
+        let update = MetricUpdate {
+            description: Some("Number of API calls".into()),
+            ..Default::default()
+        };
+        assert_eq!(
+            serde_json::to_string(&update).unwrap(),
+            r#"{"description":"Number of API calls"}"#
+        );
+    }
 }
@@ -0,0 +1,199 @@
+// The metrics of a service form a shallow tree: top-level metrics (most
+// notably "hits") can have methods, which are metrics themselves that point to
+// their parent through parent_id. 3scale does not allow deeper nesting.
+use std::collections::HashMap;
+use std::fmt;
+
+use super::Metric;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetricNode<'m> {
+    metric: &'m Metric,
+    methods: Vec<&'m Metric>,
+}
+
+impl<'m> MetricNode<'m> {
+    pub fn metric(&self) -> &'m Metric {
+        self.metric
+    }
+
+    pub fn methods(&self) -> &[&'m Metric] {
+        self.methods.as_slice()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetricHierarchy<'m> {
+    metrics: Vec<MetricNode<'m>>,
+    // metrics whose parent is not in the list
+    orphans: Vec<&'m Metric>,
+    // metrics whose parent is itself a method
+    nested: Vec<&'m Metric>,
+}
+
+impl<'m> MetricHierarchy<'m> {
+    // Build the hierarchy out of a flat list of metrics, as returned by the
+    // metrics LIST endpoint. Order of the list is preserved.
+    pub fn new(metrics: &'m [Metric]) -> Self {
+        let by_id = metrics
+            .iter()
+            .map(|m| (m.id(), m))
+            .collect::<HashMap<_, _>>();
+
+        let mut nodes = metrics
+            .iter()
+            .filter(|m| m.parent_id().is_none())
+            .map(|metric| MetricNode {
+                metric,
+                methods: Vec::new(),
+            })
+            .collect::<Vec<_>>();
+        let positions = nodes
+            .iter()
+            .enumerate()
+            .map(|(i, n)| (n.metric.id(), i))
+            .collect::<HashMap<_, _>>();
+
+        let mut orphans = Vec::new();
+        let mut nested = Vec::new();
+
+        for method in metrics.iter() {
+            let parent_id = match method.parent_id() {
+                Some(parent_id) => parent_id,
+                None => continue,
+            };
+            match positions.get(&parent_id) {
+                Some(&pos) => nodes[pos].methods.push(method),
+                None if by_id.contains_key(&parent_id) => nested.push(method),
+                None => orphans.push(method),
+            }
+        }
+
+        Self {
+            metrics: nodes,
+            orphans,
+            nested,
+        }
+    }
+
+    pub fn metrics(&self) -> &[MetricNode<'m>] {
+        self.metrics.as_slice()
+    }
+
+    pub fn find(&self, system_name: &str) -> Option<&MetricNode<'m>> {
+        self.metrics
+            .iter()
+            .find(|n| n.metric.system_name() == system_name)
+    }
+
+    // The "hits" metric and its methods, if present.
+    pub fn hits(&self) -> Option<&MetricNode<'m>> {
+        self.find("hits")
+    }
+
+    pub fn orphans(&self) -> &[&'m Metric] {
+        self.orphans.as_slice()
+    }
+
+    pub fn nested(&self) -> &[&'m Metric] {
+        self.nested.as_slice()
+    }
+
+    // Whether every method points to an existing top-level metric.
+    pub fn is_valid(&self) -> bool {
+        self.orphans.is_empty() && self.nested.is_empty()
+    }
+}
+
+impl fmt::Display for MetricHierarchy<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for node in self.metrics.iter() {
+            writeln!(
+                f,
+                "{} ({})",
+                node.metric.system_name(),
+                node.metric.friendly_name()
+            )?;
+            for method in node.methods.iter() {
+                writeln!(f, "  {} ({})", method.system_name(), method.friendly_name())?;
+            }
+        }
+        for metric in self.orphans.iter() {
+            writeln!(
+                f,
+                "{} (orphan of missing parent {})",
+                metric.system_name(),
+                metric.parent_id().unwrap_or_default()
+            )?;
+        }
+        for metric in self.nested.iter() {
+            writeln!(
+                f,
+                "{} (nested under method {})",
+                metric.system_name(),
+                metric.parent_id().unwrap_or_default()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn metric(id: u64, system_name: &str, parent_id: Option<u64>) -> Metric {
+        serde_json::from_value(serde_json::json!({
+            "id": id,
+            "name": system_name,
+            "system_name": system_name,
+            "friendly_name": system_name.to_uppercase(),
+            "description": "",
+            "unit": "hit",
+            "parent_id": parent_id,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn it_groups_methods_under_their_metric() {
+        let metrics = vec![
+            metric(1, "hits", None),
+            metric(2, "get_slash", Some(1)),
+            metric(3, "bytes", None),
+            metric(4, "post_slash", Some(1)),
+        ];
+        let hierarchy = MetricHierarchy::new(&metrics);
+
+        assert!(hierarchy.is_valid());
+        assert_eq!(hierarchy.metrics().len(), 2);
+        let hits = hierarchy.hits().unwrap();
+        let methods = hits
+            .methods()
+            .iter()
+            .map(|m| m.system_name())
+            .collect::<Vec<_>>();
+        assert_eq!(methods, vec!["get_slash", "post_slash"]);
+        assert!(hierarchy.find("bytes").unwrap().methods().is_empty());
+        assert_eq!(
+            hierarchy.to_string(),
+            "hits (HITS)\n  get_slash (GET_SLASH)\n  post_slash (POST_SLASH)\nbytes (BYTES)\n"
+        );
+    }
+
+    #[test]
+    fn it_reports_orphans_and_nested_methods() {
+        let metrics = vec![
+            metric(1, "hits", None),
+            metric(2, "get_slash", Some(1)),
+            metric(3, "deep", Some(2)),
+            metric(4, "lost", Some(99)),
+        ];
+        let hierarchy = MetricHierarchy::new(&metrics);
+
+        assert!(!hierarchy.is_valid());
+        assert_eq!(hierarchy.orphans()[0].system_name(), "lost");
+        assert_eq!(hierarchy.nested()[0].system_name(), "deep");
+        assert_eq!(hierarchy.hits().unwrap().methods().len(), 1);
+    }
+}
@@ -3,7 +3,7 @@ use straitjacket_macro::straitjacket;
 
 pub type Metadata = crate::resources::Metadata;
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum State {
     #[serde(rename(serialize = "publish", deserialize = "published"))]
     Published,
@@ -13,6 +13,14 @@ pub enum State {
     Unknown,
 }
 
+// A transition of the state of a plan.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StateEvent {
+    Publish,
+    Hide,
+}
+
 #[straitjacket(name_snake = "application_plan")]
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Plan {
@@ -32,7 +40,129 @@ pub struct Plan {
     system_name: Option<String>,
 }
 
+impl Plan {
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    pub fn system_name(&self) -> Option<&str> {
+        self.system_name.as_deref()
+    }
+
+    pub fn state(&self) -> State {
+        self.state
+    }
+
+    pub fn is_published(&self) -> bool {
+        self.state == State::Published
+    }
+
+    pub fn is_default(&self) -> bool {
+        self.default.unwrap_or(false)
+    }
+
+    pub fn is_custom(&self) -> bool {
+        self.custom.unwrap_or(false)
+    }
+
+    pub fn approval_required(&self) -> bool {
+        self.approval_required
+    }
+
+    pub fn setup_fee(&self) -> f64 {
+        self.setup_fee
+    }
+
+    pub fn cost_per_month(&self) -> f64 {
+        self.cost_per_month
+    }
+
+    pub fn trial_period_days(&self) -> Option<u64> {
+        self.trial_period_days
+    }
+
+    pub fn cancellation_period(&self) -> Option<u64> {
+        self.cancellation_period
+    }
+}
+
+// Parameters to create a plan of any kind. Cost and trial settings only
+// apply to application plans.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct NewPlan {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub approval_required: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub setup_fee: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cost_per_month: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trial_period_days: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cancellation_period: Option<u64>,
+    // Plans are created hidden unless published here.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state_event: Option<StateEvent>,
+}
+
+impl NewPlan {
+    pub fn new<S: Into<String>>(name: S) -> Self {
+        Self {
+            name: name.into(),
+            ..Default::default()
+        }
+    }
+}
+
+// Parameters to update a plan of any kind.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct PlanUpdate {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub approval_required: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub setup_fee: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cost_per_month: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trial_period_days: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cancellation_period: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state_event: Option<StateEvent>,
+}
+
+impl PlanUpdate {
+    pub fn publish() -> Self {
+        Self {
+            state_event: Some(StateEvent::Publish),
+            ..Default::default()
+        }
+    }
+
+    pub fn hide() -> Self {
+        Self {
+            state_event: Some(StateEvent::Hide),
+            ..Default::default()
+        }
+    }
+}
+
 endpoint! { LIST, GET joining [ "/admin/api/services/", "/application_plans.json"] returning Plans }
+endpoint! { READ, GET joining [ "/admin/api/services/", "/application_plans/", ".json" ] returning PlanTag }
+endpoint! { CREATE, POST joining [ "/admin/api/services/", "/application_plans.json" ] returning PlanTag }
+endpoint! { UPDATE, PUT joining [ "/admin/api/services/", "/application_plans/", ".json" ] returning PlanTag }
+endpoint! { DELETE, DELETE joining [ "/admin/api/services/", "/application_plans/", ".json" ] returning () }
+// Make the plan the default one for new applications of the service.
+endpoint! { SET_DEFAULT, PUT joining [ "/admin/api/services/", "/application_plans/", "/default.json" ] returning PlanTag }
 
 #[cfg(test)]
 mod test {
@@ -123,4 +253,31 @@ mod test {
         }
       ]
     }"# }
+
+    #[test]
+    fn it_reads_states_and_defaults() {
+        let plans: Vec<Plan> = LIST.parse_str(RESPONSE).unwrap().into();
+        assert!(plans[0].is_default());
+        assert!(plans[0].is_published());
+        assert_eq!(plans[2].state(), State::Hidden);
+        assert_eq!(plans[2].system_name(), Some("config_db8qe3hxvpk"));
+    }
+
+    #[test]
+    fn it_serializes_state_transitions_as_events() {
+        assert_eq!(
+            serde_json::to_string(&PlanUpdate::publish()).unwrap(),
+            r#"{"state_event":"publish"}"#
+        );
+        let mut plan = NewPlan::new("bronze");
+        plan.state_event = Some(StateEvent::Hide);
+        assert_eq!(
+            serde_json::to_string(&plan).unwrap(),
+            r#"{"name":"bronze","state_event":"hide"}"#
+        );
+        assert_eq!(
+            SET_DEFAULT.path(&["1", "2"]).unwrap(),
+            "/admin/api/services/1/application_plans/2/default.json"
+        );
+    }
 }
@@ -1,11 +1,18 @@
 use serde::{Deserialize, Serialize};
 use straitjacket_macro::straitjacket;
 
+use crate::error::{Error, Result};
 use crate::resources::Metadata;
 
 use super::super::{AuthenticationMode, DeploymentOption};
 
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub mod diff;
+pub mod rollback;
+
+pub use diff::ConfigDiff;
+pub use rollback::Rollback;
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum Environment {
     Sandbox,
@@ -15,6 +22,47 @@ pub enum Environment {
     Unknown,
 }
 
+impl Environment {
+    // The name of the environment in endpoint paths. Porta calls staging
+    // "sandbox" there.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Environment::Sandbox | Environment::Staging => "sandbox",
+            Environment::Production => "production",
+            Environment::Unknown => "unknown",
+        }
+    }
+
+    // The name of the environment in endpoint paths, which Porta only knows
+    // for the sandbox and production environments.
+    fn path_segment(self) -> Result<&'static str> {
+        match self {
+            Environment::Unknown => Err(Error::UnknownEnvironment),
+            _ => Ok(self.as_str()),
+        }
+    }
+
+    // Path arguments for LIST and LATEST.
+    pub fn path_args(self, service_id: &str) -> Result<[&str; 2]> {
+        Ok([service_id, self.path_segment()?])
+    }
+
+    // Path arguments for READ and PROMOTE.
+    pub fn version_path_args<'a>(
+        self,
+        service_id: &'a str,
+        version: &'a str,
+    ) -> Result<[&'a str; 3]> {
+        Ok([service_id, self.path_segment()?, version])
+    }
+}
+
+impl std::fmt::Display for Environment {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 #[serde(
     rename_all = "snake_case",
@@ -164,6 +212,10 @@ impl Content {
     pub fn proxy(&self) -> &super::Proxy {
         &self.proxy
     }
+
+    pub fn policy_chain(&self) -> &[super::policies::Policy] {
+        self.proxy.policy_chain()
+    }
 }
 
 #[straitjacket(name_tag = "ProxyConfig", name_snake = "proxy_config", plural_snake = "proxy_configs")]
@@ -184,8 +236,8 @@ impl Config {
         self.version
     }
 
-    pub fn environment(&self) -> &Environment {
-        &self.environment
+    pub fn environment(&self) -> Environment {
+        self.environment
     }
 
     pub fn content(&self) -> &Content {
@@ -193,8 +245,27 @@ impl Config {
     }
 }
 
+// Query string to promote a config to another environment. Porta only
+// promotes sandbox configs to production.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Promotion {
+    to: Environment,
+}
+
+impl Promotion {
+    pub fn to_production() -> Self {
+        Self {
+            to: Environment::Production,
+        }
+    }
+}
+
+// These take the service id and the environment as path arguments, see
+// Environment::path_args and Environment::version_path_args.
 endpoint! { LIST, GET joining [ "/admin/api/services/", "/proxy/configs/", ".json" ] returning Configs }
 endpoint! { LATEST, GET joining [ "/admin/api/services/", "/proxy/configs/", "/latest.json" ] returning ProxyConfig }
+endpoint! { READ, GET joining [ "/admin/api/services/", "/proxy/configs/", "/", ".json" ] returning ProxyConfig }
+endpoint! { PROMOTE, POST joining [ "/admin/api/services/", "/proxy/configs/", "/", "/promote.json" ] returning ProxyConfig }
 
 #[cfg(test)]
 mod tests {
@@ -704,6 +775,7 @@ mod tests {
                     secret_token: "atoken".into(),
                     service_backend_version: "1".into(),
                     valid: true,
+                    policy_chain: vec![],
                     proxy_rules: vec![mapping_rules::MappingRule {
                         id: 1,
                         metric_id: 1,
@@ -735,18 +807,27 @@ mod tests {
                 },
             ]);
             let result = serde_json::to_string_pretty(&configs);
-            match result {
-                Err(ref e) => println!("Error: {:#?}", e),
-                _ => (),
+            if let Err(ref e) = result {
+                println!("Error: {:#?}", e);
             }
             assert!(result.is_ok());
             println!("{}", result.unwrap());
         }
     }
 
-    mod latest {
+    pub(super) mod latest {
         use super::*;
 
+        // The response, shared with the tests of submodules.
+        #[cfg(feature = "client")]
+        pub(in super::super) fn response() -> &'static str {
+            RESPONSE
+        }
+
+        pub(in super::super) fn config() -> Config {
+            LATEST.parse_str(RESPONSE).unwrap().into()
+        }
+
         endpoint_test! { it_parses, LATEST, r#"{
             "proxy_config": {
               "id": 92726,
@@ -963,6 +1044,7 @@ mod tests {
                     secret_token: "atoken".into(),
                     service_backend_version: "1".into(),
                     valid: true,
+                    policy_chain: vec![],
                     proxy_rules: vec![mapping_rules::MappingRule {
                         id: 1,
                         metric_id: 1,
@@ -988,12 +1070,34 @@ mod tests {
               metadata: None
             });
             let result = serde_json::to_string_pretty(&config);
-            match result {
-                Err(ref e) => println!("Error: {:#?}", e),
-                _ => (),
+            if let Err(ref e) = result {
+                println!("Error: {:#?}", e);
             }
             assert!(result.is_ok());
             println!("{}", result.unwrap());
         }
     }
+
+    #[test]
+    fn it_builds_typed_environment_paths() {
+        assert_eq!(
+            LIST.path(&Environment::Staging.path_args("1").unwrap())
+                .unwrap(),
+            "/admin/api/services/1/proxy/configs/sandbox.json"
+        );
+        assert_eq!(
+            PROMOTE
+                .path(&Environment::Staging.version_path_args("1", "3").unwrap())
+                .unwrap(),
+            "/admin/api/services/1/proxy/configs/sandbox/3/promote.json"
+        );
+        assert_eq!(
+            serde_json::to_string(&Promotion::to_production()).unwrap(),
+            r#"{"to":"production"}"#
+        );
+        assert!(matches!(
+            Environment::Unknown.path_args("1"),
+            Err(Error::UnknownEnvironment)
+        ));
+    }
 }
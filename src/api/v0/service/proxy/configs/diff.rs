@@ -0,0 +1,390 @@
+// Structural differences between two proxy configs.
+//
+// Fields are compared through their JSON representation, so that changes
+// are reported with the same names and values Porta uses. Bookkeeping fields
+// that change on every deploy, like lock_version, are not reported.
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::fmt;
+
+use super::super::mapping_rules::MappingRule;
+use super::super::Proxy;
+use super::Config;
+
+const IGNORED_PROXY_FIELDS: &[&str] = &["lock_version", "deployed_at", "proxy_rules", "backend"];
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct FieldChange {
+    field: String,
+    from: Value,
+    to: Value,
+}
+
+impl FieldChange {
+    pub fn field(&self) -> &str {
+        self.field.as_str()
+    }
+
+    pub fn from(&self) -> &Value {
+        &self.from
+    }
+
+    pub fn to(&self) -> &Value {
+        &self.to
+    }
+}
+
+impl fmt::Display for FieldChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {} -> {}", self.field, self.from, self.to)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct MappingRuleChange {
+    id: u64,
+    changes: Vec<FieldChange>,
+}
+
+impl MappingRuleChange {
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    pub fn changes(&self) -> &[FieldChange] {
+        self.changes.as_slice()
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct MappingRulesDiff {
+    added: Vec<MappingRule>,
+    removed: Vec<MappingRule>,
+    changed: Vec<MappingRuleChange>,
+}
+
+impl MappingRulesDiff {
+    // Rules are matched by id.
+    fn new(from: &[MappingRule], to: &[MappingRule]) -> Self {
+        let mut diff = Self::default();
+
+        for rule in from.iter() {
+            match to.iter().find(|r| r.id == rule.id) {
+                Some(other) => {
+                    let changes = diff_fields(&to_value(rule), &to_value(other), &["id"]);
+                    if !changes.is_empty() {
+                        diff.changed.push(MappingRuleChange {
+                            id: rule.id,
+                            changes,
+                        });
+                    }
+                }
+                None => diff.removed.push(rule.clone()),
+            }
+        }
+        diff.added = to
+            .iter()
+            .filter(|rule| !from.iter().any(|r| r.id == rule.id))
+            .cloned()
+            .collect();
+
+        diff
+    }
+
+    pub fn added(&self) -> &[MappingRule] {
+        self.added.as_slice()
+    }
+
+    pub fn removed(&self) -> &[MappingRule] {
+        self.removed.as_slice()
+    }
+
+    pub fn changed(&self) -> &[MappingRuleChange] {
+        self.changed.as_slice()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ConfigDiff {
+    from_version: u64,
+    to_version: u64,
+    mapping_rules: MappingRulesDiff,
+    proxy: Vec<FieldChange>,
+    error_config: Vec<FieldChange>,
+    backend: Vec<FieldChange>,
+    // service settings outside of the proxy, ie. default plans
+    service: Vec<FieldChange>,
+}
+
+impl ConfigDiff {
+    pub fn new(from: &Config, to: &Config) -> Self {
+        let (from_content, to_content) = (from.content(), to.content());
+        let (from_proxy, to_proxy) = (from_content.proxy(), to_content.proxy());
+        let from_errors = to_value(from_proxy.error_config());
+        let error_config = diff_fields(&from_errors, &to_value(to_proxy.error_config()), &[]);
+        // error fields are flattened into the proxy, so report them apart
+        let proxy_skip = from_errors
+            .as_object()
+            .into_iter()
+            .flat_map(|fields| fields.keys().map(String::as_str))
+            .chain(IGNORED_PROXY_FIELDS.iter().copied())
+            .collect::<Vec<_>>();
+
+        Self {
+            from_version: from.version(),
+            to_version: to.version(),
+            mapping_rules: MappingRulesDiff::new(
+                from_proxy.mapping_rules(),
+                to_proxy.mapping_rules(),
+            ),
+            proxy: diff_fields(
+                &proxy_value(from_proxy),
+                &proxy_value(to_proxy),
+                &proxy_skip,
+            ),
+            error_config,
+            backend: diff_fields(
+                &to_value(from_proxy.backend()),
+                &to_value(to_proxy.backend()),
+                &[],
+            ),
+            service: diff_fields(&to_value(from_content), &to_value(to_content), &["proxy"]),
+        }
+    }
+
+    pub fn from_version(&self) -> u64 {
+        self.from_version
+    }
+
+    pub fn to_version(&self) -> u64 {
+        self.to_version
+    }
+
+    pub fn mapping_rules(&self) -> &MappingRulesDiff {
+        &self.mapping_rules
+    }
+
+    pub fn proxy(&self) -> &[FieldChange] {
+        self.proxy.as_slice()
+    }
+
+    pub fn error_config(&self) -> &[FieldChange] {
+        self.error_config.as_slice()
+    }
+
+    pub fn backend(&self) -> &[FieldChange] {
+        self.backend.as_slice()
+    }
+
+    pub fn service(&self) -> &[FieldChange] {
+        self.service.as_slice()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.mapping_rules.is_empty()
+            && self.proxy.is_empty()
+            && self.error_config.is_empty()
+            && self.backend.is_empty()
+            && self.service.is_empty()
+    }
+
+    // The machine-readable form of the diff.
+    pub fn to_json(&self) -> Value {
+        to_value(self)
+    }
+}
+
+impl fmt::Display for ConfigDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return writeln!(
+                f,
+                "No changes from version {} to version {}",
+                self.from_version, self.to_version
+            );
+        }
+        writeln!(
+            f,
+            "Changes from version {} to version {}:",
+            self.from_version, self.to_version
+        )?;
+
+        let rules = &self.mapping_rules;
+        if !rules.is_empty() {
+            writeln!(f, "mapping rules:")?;
+            for rule in rules.added.iter() {
+                writeln!(f, "  + {}", MappingRuleLine(rule))?;
+            }
+            for rule in rules.removed.iter() {
+                writeln!(f, "  - {}", MappingRuleLine(rule))?;
+            }
+            for rule in rules.changed.iter() {
+                writeln!(f, "  ~ {}:", rule.id)?;
+                for change in rule.changes.iter() {
+                    writeln!(f, "      {}", change)?;
+                }
+            }
+        }
+
+        for (title, changes) in [
+            ("proxy", &self.proxy),
+            ("error config", &self.error_config),
+            ("backend", &self.backend),
+            ("service", &self.service),
+        ] {
+            if changes.is_empty() {
+                continue;
+            }
+            writeln!(f, "{}:", title)?;
+            for change in changes.iter() {
+                writeln!(f, "  {}", change)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+struct MappingRuleLine<'r>(&'r MappingRule);
+
+impl fmt::Display for MappingRuleLine<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rule = self.0;
+        write!(f, "{} {} {} => ", rule.id, rule.http_method, rule.pattern)?;
+        match rule.metric_system_name.as_deref() {
+            Some(name) => write!(f, "{}", name)?,
+            None => write!(f, "metric {}", rule.metric_id)?,
+        }
+        write!(f, " +{} (position {}", rule.delta, rule.position)?;
+        if rule.last {
+            write!(f, ", last")?;
+        }
+        write!(f, ")")
+    }
+}
+
+fn to_value<T: Serialize + ?Sized>(value: &T) -> Value {
+    serde_json::to_value(value).unwrap_or(Value::Null)
+}
+
+// The URLs of a proxy are not serialized, since Porta doesn't take them back,
+// so add them here to report their changes.
+fn proxy_value(proxy: &Proxy) -> Value {
+    let mut value = to_value(proxy);
+    if let Value::Object(fields) = &mut value {
+        let url = |url: Option<&url::Url>| url.map_or(Value::Null, |url| url.as_str().into());
+        fields.insert("endpoint".into(), url(proxy.endpoint().as_ref()));
+        fields.insert("api_backend".into(), url(proxy.api_backend().as_ref()));
+        fields.insert("oauth_login_url".into(), url(proxy.oauth_login_url()));
+        fields.insert("sandbox_endpoint".into(), url(proxy.sandbox_endpoint()));
+    }
+    value
+}
+
+// Compare the fields of two JSON objects, skipping the given ones. Anything
+// that is not an object is compared as a whole.
+fn diff_fields(from: &Value, to: &Value, skip: &[&str]) -> Vec<FieldChange> {
+    let (from_map, to_map): (&Map<String, Value>, &Map<String, Value>) = match (from, to) {
+        (Value::Object(from), Value::Object(to)) => (from, to),
+        _ if from == to => return Vec::new(),
+        _ => {
+            return vec![FieldChange {
+                field: String::new(),
+                from: from.clone(),
+                to: to.clone(),
+            }]
+        }
+    };
+
+    let mut fields = from_map.keys().chain(to_map.keys()).collect::<Vec<_>>();
+    fields.sort();
+    fields.dedup();
+
+    fields
+        .into_iter()
+        .filter(|field| !skip.contains(&field.as_str()))
+        .filter_map(|field| {
+            let from = from_map.get(field).unwrap_or(&Value::Null);
+            let to = to_map.get(field).unwrap_or(&Value::Null);
+            if from == to {
+                None
+            } else {
+                Some(FieldChange {
+                    field: field.clone(),
+                    from: from.clone(),
+                    to: to.clone(),
+                })
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::tests::latest;
+    use super::*;
+
+    #[test]
+    fn it_finds_no_changes_in_the_same_config() {
+        let config = latest::config();
+        let diff = ConfigDiff::new(&config, &config);
+        assert!(diff.is_empty());
+        assert_eq!(diff.to_string(), "No changes from version 1 to version 1\n");
+    }
+
+    #[test]
+    fn it_does_not_change_how_proxies_are_serialized() {
+        let proxy = latest::config().content.proxy;
+        assert!(proxy.api_backend().is_some());
+        let json = serde_json::to_value(&proxy).unwrap();
+        assert!(json.get("api_backend").is_none());
+        assert!(proxy_value(&proxy)["api_backend"].is_string());
+    }
+
+    #[test]
+    fn it_reports_changes_by_section() {
+        let from = latest::config();
+        let mut to = from.clone();
+        to.version = 2;
+        to.content.proxy.lock_version += 1;
+        let mut rule = to.content.proxy.proxy_rules[0].clone();
+        rule.id += 1;
+        rule.pattern = "/v2".into();
+        to.content.proxy.proxy_rules[0].delta = 2;
+        to.content.proxy.proxy_rules.push(rule);
+        to.content.proxy.api_backend = Some("https://echo-api.example.com".parse().unwrap());
+        to.content.proxy.error_config.status_no_match = Some(400);
+        to.content.proxy.backend.host = "backend.example.com".into();
+        to.content.default_plans.application_plan_id = Some(1);
+
+        let diff = ConfigDiff::new(&from, &to);
+        assert!(diff.mapping_rules().removed().is_empty());
+        assert_eq!(diff.mapping_rules().added().len(), 1);
+        assert_eq!(
+            diff.mapping_rules().changed()[0].changes()[0].field(),
+            "delta"
+        );
+        let fields = |changes: &[FieldChange]| {
+            changes
+                .iter()
+                .map(|c| c.field().to_string())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(fields(diff.proxy()), vec!["api_backend"]);
+        assert_eq!(fields(diff.error_config()), vec!["error_status_no_match"]);
+        assert_eq!(fields(diff.backend()), vec!["host"]);
+        assert_eq!(fields(diff.service()), vec!["default_application_plan_id"]);
+
+        let text = diff.to_string();
+        assert!(text.starts_with("Changes from version 1 to version 2:\n"));
+        assert!(text.contains("  error_status_no_match: 404 -> 400\n"));
+
+        let json = diff.to_json();
+        assert_eq!(json["error_config"][0]["to"], 400);
+        assert_eq!(json["mapping_rules"]["added"][0]["pattern"], "/v2");
+    }
+}
@@ -0,0 +1,269 @@
+// Rolling back production re-promotes an older sandbox config version to it.
+// Porta only promotes sandbox configs, and only to production, storing the
+// promoted config as a new production version, so the rollback itself can be
+// undone the same way. Staging can't be rolled back by promotion, since its
+// configs come from deploying the proxy.
+use super::{Config, ConfigDiff, Environment};
+#[cfg(any(feature = "client", feature = "async-client"))]
+use crate::client::PlannedRequest;
+use crate::error::{Error, Result};
+
+// Only sandbox versions can be promoted. Staging shares their path segment.
+fn check_target(environment: Environment) -> Result<()> {
+    match environment {
+        Environment::Sandbox | Environment::Staging => Ok(()),
+        Environment::Production => Err(Error::InvalidRollback(
+            "production versions can't be promoted, use the sandbox version they were promoted from"
+                .into(),
+        )),
+        Environment::Unknown => Err(Error::UnknownEnvironment),
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Rollback {
+    target: Config,
+    latest: Config,
+}
+
+impl Rollback {
+    // `target` is the sandbox version to go back to and `latest` the config
+    // currently deployed in production.
+    pub fn new(target: Config, latest: Config) -> Result<Self> {
+        check_target(target.environment())?;
+        if latest.environment() != Environment::Production {
+            return Err(Error::InvalidRollback(format!(
+                "only production can be rolled back, not {}",
+                latest.environment()
+            )));
+        }
+        Ok(Self { target, latest })
+    }
+
+    pub fn target(&self) -> &Config {
+        &self.target
+    }
+
+    pub fn latest(&self) -> &Config {
+        &self.latest
+    }
+
+    // What the rollback would change in the production configuration.
+    pub fn changes(&self) -> ConfigDiff {
+        ConfigDiff::new(&self.latest, &self.target)
+    }
+
+    // Whether the target is already what is deployed.
+    pub fn is_noop(&self) -> bool {
+        self.changes().is_empty()
+    }
+
+    // Fetch the version to go back to and the latest production config. The
+    // version must be one listed for the sandbox (or staging) environment.
+    #[cfg(feature = "client")]
+    pub fn prepare(
+        client: &crate::client::Client,
+        service_id: &str,
+        environment: Environment,
+        version: u64,
+    ) -> Result<Self> {
+        let [target, latest] = Self::prepare_requests(service_id, environment, version)?;
+        let target = client.call_planned::<super::ProxyConfig>(&target)?;
+        let latest = client.call_planned::<super::ProxyConfig>(&latest)?;
+        Self::new(target.into(), latest.into())
+    }
+
+    // Promote the target version to production, returning the newly deployed
+    // config.
+    #[cfg(feature = "client")]
+    pub fn apply(&self, client: &crate::client::Client, service_id: &str) -> Result<Config> {
+        let config = client.call_planned::<super::ProxyConfig>(&self.request(service_id)?)?;
+        Ok(config.into())
+    }
+
+    #[cfg(feature = "async-client")]
+    pub async fn prepare_async(
+        client: &crate::client::AsyncClient,
+        service_id: &str,
+        environment: Environment,
+        version: u64,
+    ) -> Result<Self> {
+        let [target, latest] = Self::prepare_requests(service_id, environment, version)?;
+        let target = client.call_planned::<super::ProxyConfig>(&target).await?;
+        let latest = client.call_planned::<super::ProxyConfig>(&latest).await?;
+        Self::new(target.into(), latest.into())
+    }
+
+    #[cfg(feature = "async-client")]
+    pub async fn apply_async(
+        &self,
+        client: &crate::client::AsyncClient,
+        service_id: &str,
+    ) -> Result<Config> {
+        let config = client
+            .call_planned::<super::ProxyConfig>(&self.request(service_id)?)
+            .await?;
+        Ok(config.into())
+    }
+
+    #[cfg(any(feature = "client", feature = "async-client"))]
+    fn prepare_requests(
+        service_id: &str,
+        environment: Environment,
+        version: u64,
+    ) -> Result<[PlannedRequest; 2]> {
+        check_target(environment)?;
+        let version = version.to_string();
+        Ok([
+            PlannedRequest::new(
+                &super::READ,
+                &environment.version_path_args(service_id, &version)?,
+                None::<&()>,
+                None::<&()>,
+            )?,
+            PlannedRequest::new(
+                &super::LATEST,
+                &Environment::Production.path_args(service_id)?,
+                None::<&()>,
+                None::<&()>,
+            )?,
+        ])
+    }
+
+    #[cfg(any(feature = "client", feature = "async-client"))]
+    fn request(&self, service_id: &str) -> Result<PlannedRequest> {
+        let version = self.target.version().to_string();
+        PlannedRequest::new(
+            &super::PROMOTE,
+            &Environment::Sandbox.version_path_args(service_id, &version)?,
+            Some(&super::Promotion::to_production()),
+            None::<&()>,
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::tests::latest;
+    use super::*;
+
+    #[test]
+    fn it_is_a_noop_for_the_deployed_config() {
+        let latest = latest::config();
+        let mut target = latest.clone();
+        target.environment = Environment::Sandbox;
+        let rollback = Rollback::new(target, latest).unwrap();
+        assert!(rollback.is_noop());
+        assert!(rollback.changes().is_empty());
+    }
+
+    #[test]
+    fn it_lists_the_changed_sections() {
+        let latest = latest::config();
+        let mut target = latest.clone();
+        target.environment = Environment::Sandbox;
+        target.version -= 1;
+        target.content.proxy.lock_version -= 1;
+        target.content.proxy.proxy_rules.pop();
+        target.content.proxy.error_config.status_no_match = Some(400);
+
+        let rollback = Rollback::new(target, latest).unwrap();
+        assert!(!rollback.is_noop());
+        let changes = rollback.changes();
+        assert_eq!(changes.mapping_rules().removed().len(), 1);
+        assert_eq!(changes.error_config().len(), 1);
+        assert!(changes.proxy().is_empty());
+    }
+
+    #[test]
+    fn it_only_rolls_production_back_to_sandbox_versions() {
+        let latest = latest::config();
+        assert!(matches!(
+            Rollback::new(latest.clone(), latest.clone()),
+            Err(Error::InvalidRollback(_))
+        ));
+        let mut sandbox = latest.clone();
+        sandbox.environment = Environment::Sandbox;
+        assert!(matches!(
+            Rollback::new(sandbox.clone(), sandbox),
+            Err(Error::InvalidRollback(_))
+        ));
+    }
+
+    #[cfg(feature = "client")]
+    mod prepare {
+        use super::*;
+        use crate::client::test_server::TestServer;
+
+        fn prepare(environment: Environment) -> (TestServer, Result<Rollback>) {
+            let sandbox = latest::response().replace(r#""production""#, r#""sandbox""#);
+            let (server, client) = TestServer::with_client(vec![
+                (200, sandbox),
+                (200, latest::response().to_string()),
+            ]);
+            let rollback = Rollback::prepare(&client, "2555417777820", environment, 1);
+            (server, rollback)
+        }
+
+        #[test]
+        fn it_reads_sandbox_versions() {
+            let (server, rollback) = prepare(Environment::Sandbox);
+            assert_eq!(
+                rollback.unwrap().target().environment(),
+                Environment::Sandbox
+            );
+            assert_eq!(
+                server.received().path_only(),
+                "/admin/api/services/2555417777820/proxy/configs/sandbox/1.json"
+            );
+            assert_eq!(
+                server.received().path_only(),
+                "/admin/api/services/2555417777820/proxy/configs/production/latest.json"
+            );
+        }
+
+        #[test]
+        fn it_reads_staging_versions_from_the_sandbox() {
+            let (server, rollback) = prepare(Environment::Staging);
+            assert!(rollback.is_ok());
+            assert_eq!(
+                server.received().path_only(),
+                "/admin/api/services/2555417777820/proxy/configs/sandbox/1.json"
+            );
+        }
+
+        #[test]
+        fn it_rejects_production_versions() {
+            let (_, rollback) = prepare(Environment::Production);
+            assert!(matches!(rollback, Err(Error::InvalidRollback(_))));
+        }
+
+        #[test]
+        fn it_rejects_unknown_environments() {
+            let (_, rollback) = prepare(Environment::Unknown);
+            assert!(matches!(rollback, Err(Error::UnknownEnvironment)));
+        }
+    }
+
+    #[cfg(feature = "client")]
+    #[test]
+    fn it_promotes_the_target_version() {
+        use crate::client::test_server::TestServer;
+
+        let mut target = latest::config();
+        target.environment = Environment::Sandbox;
+        let mut latest = latest::config();
+        latest.version = 2;
+        let (server, client) = TestServer::ok_with_client(latest::response());
+
+        let rollback = Rollback::new(target, latest).unwrap();
+        rollback.apply(&client, "2555417777820").unwrap();
+        let request = server.received();
+        assert_eq!(request.method, "POST");
+        assert_eq!(
+            request.path_only(),
+            "/admin/api/services/2555417777820/proxy/configs/sandbox/1/promote.json"
+        );
+        assert!(request.has_query_pair("to", "production"));
+    }
+}
@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 use straitjacket_macro::straitjacket;
 
+#[cfg(any(feature = "client", feature = "async-client"))]
+use crate::client::PlannedRequest;
 use crate::resources::Metadata;
 
 #[straitjacket]
@@ -20,7 +22,259 @@ pub struct MappingRule {
     // querystring_parameters - unknown type
 }
 
+// Parameters to create a mapping rule.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct NewMappingRule {
+    pub http_method: String,
+    pub pattern: String,
+    pub delta: u64,
+    pub metric_id: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub position: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub redirect_url: Option<String>,
+}
+
+impl NewMappingRule {
+    pub fn new<M: Into<String>, P: Into<String>>(
+        http_method: M,
+        pattern: P,
+        delta: u64,
+        metric_id: u64,
+    ) -> Self {
+        Self {
+            http_method: http_method.into(),
+            pattern: pattern.into(),
+            delta,
+            metric_id,
+            ..Default::default()
+        }
+    }
+}
+
+impl From<&MappingRule> for NewMappingRule {
+    fn from(rule: &MappingRule) -> Self {
+        Self {
+            http_method: rule.http_method.clone(),
+            pattern: rule.pattern.clone(),
+            delta: rule.delta,
+            metric_id: rule.metric_id,
+            position: Some(rule.position),
+            last: Some(rule.last),
+            redirect_url: rule.redirect_url.clone(),
+        }
+    }
+}
+
+// Parameters to update a mapping rule.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct MappingRuleUpdate {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub http_method: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delta: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metric_id: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub position: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last: Option<bool>,
+    // An empty string removes the redirection.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub redirect_url: Option<String>,
+}
+
+impl MappingRuleUpdate {
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+
+    // The changes needed for `current` to match `desired`, not considering
+    // the position.
+    fn between(current: &MappingRule, desired: &MappingRule) -> Self {
+        fn changed<T: PartialEq + Clone>(current: &T, desired: &T) -> Option<T> {
+            if current != desired {
+                Some(desired.clone())
+            } else {
+                None
+            }
+        }
+
+        Self {
+            http_method: changed(&current.http_method, &desired.http_method),
+            pattern: changed(&current.pattern, &desired.pattern),
+            delta: changed(&current.delta, &desired.delta),
+            metric_id: changed(&current.metric_id, &desired.metric_id),
+            position: None,
+            last: changed(&current.last, &desired.last),
+            redirect_url: changed(&current.redirect_url, &desired.redirect_url)
+                .map(Option::unwrap_or_default),
+        }
+    }
+}
+
 endpoint! { LIST, GET joining [ "/admin/api/services/", "/proxy/mapping_rules.json" ] returning MappingRules }
+endpoint! { READ, GET joining [ "/admin/api/services/", "/proxy/mapping_rules/", ".json" ] returning MappingRuleTag }
+endpoint! { CREATE, POST joining [ "/admin/api/services/", "/proxy/mapping_rules.json" ] returning MappingRuleTag }
+endpoint! { UPDATE, PATCH joining [ "/admin/api/services/", "/proxy/mapping_rules/", ".json" ] returning MappingRuleTag }
+endpoint! { DELETE, DELETE joining [ "/admin/api/services/", "/proxy/mapping_rules/", ".json" ] returning () }
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MappingRuleChange {
+    Create(NewMappingRule),
+    Update { id: u64, update: MappingRuleUpdate },
+    Delete { id: u64 },
+}
+
+// The changes that turn a service's current mapping rules into a desired,
+// ordered list of rules.
+//
+// Rules in the desired list are matched to existing ones by id when it is
+// set (non-zero), so that changing their method or pattern updates them in
+// place, or else by HTTP method and pattern. Existing rules that
+// match nothing are deleted, and desired rules that match nothing are
+// created. The position of desired rules is given by their place in the
+// list, so their `position` field is ignored.
+//
+// Porta keeps positions contiguous, shifting the following rules whenever
+// one is inserted, moved or removed. The plan accounts for that so that
+// rules are only moved when they would not end up in place otherwise, and
+// changes must be applied in the order they are listed.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MappingRulesPlan {
+    changes: Vec<MappingRuleChange>,
+}
+
+impl MappingRulesPlan {
+    pub fn new(current: &[MappingRule], desired: &[MappingRule]) -> Self {
+        let mut current = current.iter().collect::<Vec<_>>();
+        current.sort_by_key(|rule| rule.position);
+
+        let mut matches: Vec<Option<&MappingRule>> = vec![None; desired.len()];
+        let mut taken = vec![false; current.len()];
+
+        let mut find = |pred: &dyn Fn(&MappingRule) -> bool| {
+            let idx = (0..current.len()).find(|&i| !taken[i] && pred(current[i]))?;
+            taken[idx] = true;
+            Some(current[idx])
+        };
+        for (i, rule) in desired.iter().enumerate() {
+            if rule.id != 0 {
+                matches[i] = find(&|c| c.id == rule.id);
+            }
+        }
+        for (i, rule) in desired.iter().enumerate() {
+            if matches[i].is_none() && rule.id == 0 {
+                matches[i] =
+                    find(&|c| c.http_method == rule.http_method && c.pattern == rule.pattern);
+            }
+        }
+
+        let mut changes = current
+            .iter()
+            .zip(taken.iter())
+            .filter(|(_, &taken)| !taken)
+            .map(|(rule, _)| MappingRuleChange::Delete { id: rule.id })
+            .collect::<Vec<_>>();
+
+        // Ids of the remaining rules in their order after each change, with
+        // None standing for rules created by the plan.
+        let mut order = current
+            .iter()
+            .zip(taken.iter())
+            .filter(|(_, &taken)| taken)
+            .map(|(rule, _)| Some(rule.id))
+            .collect::<Vec<_>>();
+
+        for (i, (rule, existing)) in desired.iter().zip(matches).enumerate() {
+            let position = i as u64 + 1;
+            match existing {
+                Some(existing) => {
+                    let mut update = MappingRuleUpdate::between(existing, rule);
+                    let idx = order
+                        .iter()
+                        .position(|id| *id == Some(existing.id))
+                        .unwrap_or(i);
+                    if idx != i {
+                        order.remove(idx);
+                        order.insert(i, Some(existing.id));
+                        update.position = Some(position);
+                    }
+                    if !update.is_empty() {
+                        changes.push(MappingRuleChange::Update {
+                            id: existing.id,
+                            update,
+                        });
+                    }
+                }
+                None => {
+                    order.insert(i, None);
+                    let mut new_rule = NewMappingRule::from(rule);
+                    new_rule.position = Some(position);
+                    changes.push(MappingRuleChange::Create(new_rule));
+                }
+            }
+        }
+
+        Self { changes }
+    }
+
+    pub fn changes(&self) -> &[MappingRuleChange] {
+        self.changes.as_slice()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    // Apply the changes in order to the given service, stopping at the first
+    // failure.
+    #[cfg(feature = "client")]
+    pub fn apply(
+        &self,
+        client: &crate::client::Client,
+        service_id: &str,
+    ) -> crate::error::Result<()> {
+        client.send_planned(&self.requests(service_id)?)
+    }
+
+    #[cfg(feature = "async-client")]
+    pub async fn apply_async(
+        &self,
+        client: &crate::client::AsyncClient,
+        service_id: &str,
+    ) -> crate::error::Result<()> {
+        client.send_planned(&self.requests(service_id)?).await
+    }
+
+    #[cfg(any(feature = "client", feature = "async-client"))]
+    fn requests(&self, service_id: &str) -> crate::error::Result<Vec<PlannedRequest>> {
+        self.changes
+            .iter()
+            .map(|change| match change {
+                MappingRuleChange::Create(new_rule) => {
+                    PlannedRequest::new(&CREATE, &[service_id], None::<&()>, Some(new_rule))
+                }
+                MappingRuleChange::Update { id, update } => PlannedRequest::new(
+                    &UPDATE,
+                    &[service_id, &id.to_string()],
+                    None::<&()>,
+                    Some(update),
+                ),
+                MappingRuleChange::Delete { id } => PlannedRequest::new(
+                    &DELETE,
+                    &[service_id, &id.to_string()],
+                    None::<&()>,
+                    None::<&()>,
+                ),
+            })
+            .collect()
+    }
+}
 
 #[cfg(test)]
 mod tests {
@@ -114,11 +368,140 @@ mod tests {
             },
         ]);
         let result = serde_json::to_string_pretty(&mapping_rules);
-        match result {
-            Err(ref e) => println!("Error: {:#?}", e),
-            _ => (),
+        if let Err(ref e) = result {
+            println!("Error: {:#?}", e);
         }
         assert!(result.is_ok());
         println!("{}", result.unwrap());
     }
+
+    mod plan {
+        use super::*;
+
+        fn rule(id: u64, http_method: &str, pattern: &str, position: u64) -> MappingRule {
+            MappingRule {
+                id,
+                metric_id: 1,
+                http_method: http_method.into(),
+                pattern: pattern.into(),
+                delta: 1,
+                position,
+                ..Default::default()
+            }
+        }
+
+        fn current() -> Vec<MappingRule> {
+            vec![
+                rule(10, "GET", "/a", 1),
+                rule(11, "GET", "/b", 2),
+                rule(12, "GET", "/c", 3),
+            ]
+        }
+
+        #[test]
+        fn it_does_nothing_when_rules_match() {
+            let desired = vec![
+                rule(0, "GET", "/a", 0),
+                rule(0, "GET", "/b", 0),
+                rule(0, "GET", "/c", 0),
+            ];
+            assert!(MappingRulesPlan::new(&current(), &desired).is_empty());
+        }
+
+        #[test]
+        fn it_creates_in_place_without_moving_following_rules() {
+            let desired = vec![
+                rule(0, "POST", "/new", 0),
+                rule(0, "GET", "/a", 0),
+                rule(0, "GET", "/b", 0),
+                rule(0, "GET", "/c", 0),
+            ];
+            let plan = MappingRulesPlan::new(&current(), &desired);
+            assert_eq!(plan.changes().len(), 1);
+            match &plan.changes()[0] {
+                MappingRuleChange::Create(new_rule) => {
+                    assert_eq!(new_rule.pattern, "/new");
+                    assert_eq!(new_rule.position, Some(1));
+                }
+                change => panic!("unexpected change {:?}", change),
+            }
+        }
+
+        #[test]
+        fn it_moves_deletes_and_updates() {
+            let mut changed = rule(0, "GET", "/a", 0);
+            changed.delta = 5;
+            let desired = vec![rule(12, "GET", "/c", 0), changed];
+            let plan = MappingRulesPlan::new(&current(), &desired);
+            assert_eq!(
+                plan.changes(),
+                &[
+                    MappingRuleChange::Delete { id: 11 },
+                    MappingRuleChange::Update {
+                        id: 12,
+                        update: MappingRuleUpdate {
+                            position: Some(1),
+                            ..Default::default()
+                        }
+                    },
+                    MappingRuleChange::Update {
+                        id: 10,
+                        update: MappingRuleUpdate {
+                            delta: Some(5),
+                            ..Default::default()
+                        }
+                    },
+                ]
+            );
+        }
+
+        #[test]
+        fn it_updates_rules_matched_by_id_in_place() {
+            let desired = vec![
+                rule(10, "GET", "/a", 0),
+                rule(11, "POST", "/b/{id}", 0),
+                rule(12, "GET", "/c", 0),
+            ];
+            let plan = MappingRulesPlan::new(&current(), &desired);
+            assert_eq!(
+                plan.changes(),
+                &[MappingRuleChange::Update {
+                    id: 11,
+                    update: MappingRuleUpdate {
+                        http_method: Some("POST".into()),
+                        pattern: Some("/b/{id}".into()),
+                        ..Default::default()
+                    }
+                }]
+            );
+            assert_eq!(UPDATE.method(), http::Method::PATCH);
+        }
+
+        #[cfg(feature = "client")]
+        #[test]
+        fn it_applies_the_changes_in_order() {
+            use crate::client::test_server::TestServer;
+
+            let desired = vec![rule(0, "GET", "/a", 0), rule(0, "PUT", "/d", 0)];
+            let plan = MappingRulesPlan::new(&current(), &desired);
+            let created = r#"{ "mapping_rule": { "id": 13, "metric_id": 1, "pattern": "/d", "http_method": "PUT", "delta": 1, "position": 2, "last": false } }"#;
+            let (server, client) = TestServer::with_client(vec![
+                (200, String::new()),
+                (200, String::new()),
+                (201, created.to_string()),
+            ]);
+
+            plan.apply(&client, "1").unwrap();
+            let first = server.received();
+            assert_eq!(first.method, "DELETE");
+            assert_eq!(
+                first.path_only(),
+                "/admin/api/services/1/proxy/mapping_rules/11.json"
+            );
+            assert_eq!(server.received().method, "DELETE");
+            let create = server.received();
+            assert_eq!(create.method, "POST");
+            assert!(create.body.contains(r#""position":2"#));
+        }
+    }
 }
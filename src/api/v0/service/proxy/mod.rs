@@ -7,16 +7,15 @@ use super::AuthenticationMode;
 
 pub mod configs;
 pub mod mapping_rules;
+pub mod policies;
 
-pub(self) fn parse_url<'de, D: Deserializer<'de>>(deserializer: D) -> Result<url::Url, D::Error> {
+fn parse_url<'de, D: Deserializer<'de>>(deserializer: D) -> Result<url::Url, D::Error> {
     let string: String = Deserialize::deserialize(deserializer)?;
     let url = url::Url::parse(&string);
     url.map_err(serde::de::Error::custom)
 }
 
-pub(self) fn parse_url_opt<'de, D: Deserializer<'de>>(
-    deserializer: D,
-) -> Result<Option<url::Url>, D::Error> {
+fn parse_url_opt<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<url::Url>, D::Error> {
     let string: Result<String, _> = Deserialize::deserialize(deserializer);
     if string.is_err() {
         return Ok(None);
@@ -26,10 +25,7 @@ pub(self) fn parse_url_opt<'de, D: Deserializer<'de>>(
     url.map(Some).map_err(serde::de::Error::custom)
 }
 
-pub(self) fn serialize_url<S: Serializer>(
-    url: &url::Url,
-    serializer: S,
-) -> Result<S::Ok, S::Error> {
+fn serialize_url<S: Serializer>(url: &url::Url, serializer: S) -> Result<S::Ok, S::Error> {
     let url_s = url.to_string();
     serializer.serialize_str(&url_s)
 }
@@ -196,15 +192,10 @@ pub struct Proxy {
     hosts: Vec<String>,
     backend: Backend,
     proxy_rules: Vec<mapping_rules::MappingRule>,
+    #[serde(default)]
+    policy_chain: Vec<policies::Policy>,
     // These below are currently ignored:
     //"api_test_success": null,
-    //"policy_chain": [
-    //  {
-    //    "name": "apicast",
-    //    "version": "builtin",
-    //    "configuration": {}
-    //  }
-    //],
 }
 
 impl Proxy {
@@ -293,8 +284,131 @@ impl Proxy {
     pub fn mapping_rules(&self) -> &[mapping_rules::MappingRule] {
         self.proxy_rules.as_slice()
     }
+
+    pub fn policy_chain(&self) -> &[policies::Policy] {
+        self.policy_chain.as_slice()
+    }
+
+    pub fn sandbox_endpoint(&self) -> Option<&url::Url> {
+        self.sandbox_endpoint.as_ref()
+    }
+
+    pub fn deployed_at(&self) -> Option<&str> {
+        self.deployed_at.as_deref()
+    }
+
+    pub fn lock_version(&self) -> u64 {
+        self.lock_version
+    }
+}
+
+// Error responses to update.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct ErrorConfigUpdate {
+    #[serde(rename = "error_auth_failed", skip_serializing_if = "Option::is_none")]
+    pub auth_failed: Option<String>,
+    #[serde(
+        rename = "error_status_auth_failed",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub status_auth_failed: Option<u64>,
+    #[serde(
+        rename = "error_headers_auth_failed",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub headers_auth_failed: Option<String>,
+    #[serde(rename = "error_auth_missing", skip_serializing_if = "Option::is_none")]
+    pub auth_missing: Option<String>,
+    #[serde(
+        rename = "error_status_auth_missing",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub status_auth_missing: Option<u64>,
+    #[serde(
+        rename = "error_headers_auth_missing",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub headers_auth_missing: Option<String>,
+    #[serde(rename = "error_no_match", skip_serializing_if = "Option::is_none")]
+    pub no_match: Option<String>,
+    #[serde(
+        rename = "error_status_no_match",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub status_no_match: Option<u64>,
+    #[serde(
+        rename = "error_headers_no_match",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub headers_no_match: Option<String>,
+    #[serde(
+        rename = "error_limits_exceeded",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub limits_exceeded: Option<String>,
+    #[serde(
+        rename = "error_status_limits_exceeded",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub status_limits_exceeded: Option<u64>,
+    #[serde(
+        rename = "error_headers_limits_exceeded",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub headers_limits_exceeded: Option<String>,
 }
 
+// Parameters to update a proxy.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct ProxyUpdate {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub endpoint: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sandbox_endpoint: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub api_backend: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub credentials_location: Option<CredentialsLocation>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auth_app_key: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auth_app_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auth_user_key: Option<String>,
+    #[serde(flatten)]
+    pub error_config: ErrorConfigUpdate,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub secret_token: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hostname_rewrite: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub api_test_path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub oidc_issuer_endpoint: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub oidc_issuer_type: Option<String>,
+    #[serde(
+        rename = "jwt_claim_with_client_id",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub jwt_claim_client_id: Option<String>,
+    #[serde(
+        rename = "jwt_claim_with_client_id_type",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub jwt_claim_client_type: Option<JWTClaimClientIDType>,
+    // Set this to the proxy's lock_version to have Porta reject the update
+    // if the proxy was changed in the meantime.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lock_version: Option<u64>,
+}
+
+endpoint! { READ, GET joining [ "/admin/api/services/", "/proxy.json" ] returning ProxyTag }
+endpoint! { UPDATE, PATCH joining [ "/admin/api/services/", "/proxy.json" ] returning ProxyTag }
+// Deploy the current proxy configuration to the staging environment. Use
+// configs::PROMOTE to move a staging config to production.
+endpoint! { DEPLOY, POST joining [ "/admin/api/services/", "/proxy/deploy.json" ] returning ProxyTag }
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -377,6 +491,38 @@ mod tests {
         if let Err(e) = &proxy {
             println!("Error: {:#?}", e);
         }
-        assert!(proxy.is_ok())
+        assert!(proxy.is_ok());
+        assert_eq!(proxy.unwrap().policy_chain()[0].name(), "apicast");
+    }
+
+    #[test]
+    fn it_serializes_updates_with_porta_field_names() {
+        let update = ProxyUpdate {
+            credentials_location: Some(CredentialsLocation::Query),
+            error_config: ErrorConfigUpdate {
+                status_no_match: Some(400),
+                ..Default::default()
+            },
+            lock_version: Some(3),
+            ..Default::default()
+        };
+        let json = serde_json::to_value(&update).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "credentials_location": "query",
+                "error_status_no_match": 400,
+                "lock_version": 3
+            })
+        );
+    }
+
+    #[test]
+    fn it_builds_deploy_paths() {
+        assert_eq!(DEPLOY.method(), &http::Method::POST);
+        assert_eq!(
+            DEPLOY.path(&["1"]).unwrap(),
+            "/admin/api/services/1/proxy/deploy.json"
+        );
     }
 }
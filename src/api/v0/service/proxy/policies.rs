@@ -0,0 +1,785 @@
+// The APIcast policy chain of a proxy.
+//
+// Configurations of the common built-in policies are typed, while any other
+// policy keeps its configuration as raw JSON. A built-in policy whose
+// configuration does not match its type, or would not be written back as it
+// was read, ie. because of keys from newer APIcast versions, is kept as raw
+// JSON too, so that nothing is lost when the chain is sent back to Porta.
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
+use std::fmt;
+
+#[cfg(feature = "policy-schemas")]
+pub mod validation;
+
+pub const BUILTIN: &str = "builtin";
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ValueType {
+    Plain,
+    Liquid,
+    #[serde(other)]
+    Unknown,
+}
+
+// CORS policy
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Cors {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allow_headers: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allow_methods: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allow_origin: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allow_credentials: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_age: Option<u64>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HeaderOp {
+    Set,
+    Add,
+    Push,
+    Delete,
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct HeaderCommand {
+    pub op: HeaderOp,
+    pub header: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value_type: Option<ValueType>,
+}
+
+// Header modification policy
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Headers {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub request: Vec<HeaderCommand>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub response: Vec<HeaderCommand>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RewriteOp {
+    Sub,
+    Gsub,
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RewriteCommand {
+    pub op: RewriteOp,
+    pub regex: String,
+    pub replace: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub options: Option<String>,
+    #[serde(rename = "break", skip_serializing_if = "Option::is_none")]
+    pub break_: Option<bool>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub methods: Vec<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct QueryArgCommand {
+    pub op: HeaderOp,
+    pub arg: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value_type: Option<ValueType>,
+}
+
+// URL rewriting policy
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct UrlRewriting {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub commands: Vec<RewriteCommand>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub query_args_commands: Vec<QueryArgCommand>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LimiterScope {
+    Global,
+    Service,
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LimiterKey {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name_type: Option<ValueType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scope: Option<LimiterScope>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ConnectionLimiter {
+    pub key: LimiterKey,
+    pub conn: u64,
+    pub burst: u64,
+    pub delay: f64,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LeakyBucketLimiter {
+    pub key: LimiterKey,
+    pub rate: u64,
+    pub burst: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FixedWindowLimiter {
+    pub key: LimiterKey,
+    pub count: u64,
+    pub window: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LimiterError {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status_code: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error_handling: Option<String>,
+}
+
+// Edge limiting policy
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RateLimit {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub connection_limiters: Vec<ConnectionLimiter>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub leaky_bucket_limiters: Vec<LeakyBucketLimiter>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fixed_window_limiters: Vec<FixedWindowLimiter>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub redis_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limits_exceeded_error: Option<LimiterError>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub configuration_error: Option<LimiterError>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IpCheckType {
+    Whitelist,
+    Blacklist,
+    #[serde(other)]
+    Unknown,
+}
+
+// IP check policy
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct IpCheck {
+    pub check_type: IpCheckType,
+    #[serde(default)]
+    pub ips: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error_msg: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub client_ip_sources: Vec<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RoutingOperation {
+    #[serde(rename = "match")]
+    pub matches: String,
+    pub op: String,
+    pub value: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value_type: Option<ValueType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub header_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub query_arg_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub jwt_claim_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cookie_name: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RoutingCondition {
+    #[serde(default)]
+    pub operations: Vec<RoutingOperation>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub combine_op: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RoutingRule {
+    pub url: String,
+    #[serde(default)]
+    pub condition: RoutingCondition,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub owner_id: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub owner_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub replace_path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub host_header: Option<String>,
+}
+
+// Routing policy
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Routing {
+    #[serde(default)]
+    pub rules: Vec<RoutingRule>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum PolicyConfiguration {
+    Cors(Cors),
+    Headers(Headers),
+    UrlRewriting(UrlRewriting),
+    RateLimit(RateLimit),
+    IpCheck(IpCheck),
+    Routing(Routing),
+    // Any other policy, or a built-in one that could not be typed.
+    Other(Value),
+}
+
+impl PolicyConfiguration {
+    // The name of the policy for typed configurations.
+    pub fn policy_name(&self) -> Option<&'static str> {
+        match self {
+            PolicyConfiguration::Cors(_) => Some("cors"),
+            PolicyConfiguration::Headers(_) => Some("headers"),
+            PolicyConfiguration::UrlRewriting(_) => Some("url_rewriting"),
+            PolicyConfiguration::RateLimit(_) => Some("rate_limit"),
+            PolicyConfiguration::IpCheck(_) => Some("ip_check"),
+            PolicyConfiguration::Routing(_) => Some("routing"),
+            PolicyConfiguration::Other(_) => None,
+        }
+    }
+
+    fn parse(name: &str, configuration: Value) -> Self {
+        fn typed<T, F>(configuration: &Value, variant: F) -> Option<PolicyConfiguration>
+        where
+            T: for<'de> Deserialize<'de>,
+            F: FnOnce(T) -> PolicyConfiguration,
+        {
+            T::deserialize(configuration)
+                .ok()
+                .map(variant)
+                .filter(|typed| typed.to_value() == *configuration)
+        }
+
+        let parsed = match name {
+            "cors" => typed(&configuration, PolicyConfiguration::Cors),
+            "headers" => typed(&configuration, PolicyConfiguration::Headers),
+            "url_rewriting" => typed(&configuration, PolicyConfiguration::UrlRewriting),
+            "rate_limit" => typed(&configuration, PolicyConfiguration::RateLimit),
+            "ip_check" => typed(&configuration, PolicyConfiguration::IpCheck),
+            "routing" => typed(&configuration, PolicyConfiguration::Routing),
+            _ => None,
+        };
+        parsed.unwrap_or(PolicyConfiguration::Other(configuration))
+    }
+
+    pub fn to_value(&self) -> Value {
+        let value = match self {
+            PolicyConfiguration::Cors(c) => serde_json::to_value(c),
+            PolicyConfiguration::Headers(c) => serde_json::to_value(c),
+            PolicyConfiguration::UrlRewriting(c) => serde_json::to_value(c),
+            PolicyConfiguration::RateLimit(c) => serde_json::to_value(c),
+            PolicyConfiguration::IpCheck(c) => serde_json::to_value(c),
+            PolicyConfiguration::Routing(c) => serde_json::to_value(c),
+            PolicyConfiguration::Other(v) => return v.clone(),
+        };
+        // these types always serialize into JSON objects
+        value.unwrap_or_else(|_| Value::Object(Default::default()))
+    }
+}
+
+// The wire format of a policy.
+#[derive(Serialize, Deserialize)]
+struct RawPolicy {
+    name: String,
+    version: String,
+    #[serde(default)]
+    configuration: Value,
+    #[serde(default = "enabled_default")]
+    enabled: bool,
+}
+
+fn enabled_default() -> bool {
+    true
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Policy {
+    name: String,
+    version: String,
+    configuration: PolicyConfiguration,
+    enabled: bool,
+}
+
+impl Policy {
+    // A built-in policy with a typed configuration.
+    pub fn builtin(configuration: PolicyConfiguration) -> Option<Self> {
+        let name = configuration.policy_name()?;
+        Some(Self {
+            name: name.to_string(),
+            version: BUILTIN.to_string(),
+            configuration,
+            enabled: true,
+        })
+    }
+
+    // Any policy, ie. a custom one, with a raw JSON configuration.
+    pub fn new<N: Into<String>, V: Into<String>>(
+        name: N,
+        version: V,
+        configuration: Value,
+    ) -> Self {
+        let name = name.into();
+        Self {
+            configuration: PolicyConfiguration::parse(&name, configuration),
+            name,
+            version: version.into(),
+            enabled: true,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    pub fn version(&self) -> &str {
+        self.version.as_str()
+    }
+
+    pub fn configuration(&self) -> &PolicyConfiguration {
+        &self.configuration
+    }
+
+    pub fn configuration_mut(&mut self) -> &mut PolicyConfiguration {
+        &mut self.configuration
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+}
+
+impl<'de> Deserialize<'de> for Policy {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let raw = RawPolicy::deserialize(deserializer)?;
+        Ok(Self {
+            configuration: PolicyConfiguration::parse(&raw.name, raw.configuration),
+            name: raw.name,
+            version: raw.version,
+            enabled: raw.enabled,
+        })
+    }
+}
+
+impl Serialize for Policy {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        RawPolicy {
+            name: self.name.clone(),
+            version: self.version.clone(),
+            configuration: self.configuration.to_value(),
+            enabled: self.enabled,
+        }
+        .serialize(serializer)
+    }
+}
+
+// A policy configuration that does not match the schema of its policy, as
+// found by schema validation.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PolicyViolation {
+    index: usize,
+    policy: String,
+    path: String,
+    message: String,
+}
+
+impl PolicyViolation {
+    // Position of the policy in the chain.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    pub fn policy(&self) -> &str {
+        self.policy.as_str()
+    }
+
+    // JSON pointer to the offending value within the configuration.
+    pub fn path(&self) -> &str {
+        self.path.as_str()
+    }
+
+    pub fn message(&self) -> &str {
+        self.message.as_str()
+    }
+}
+
+impl fmt::Display for PolicyViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "policy #{} ({}) at '{}': {}",
+            self.index, self.policy, self.path, self.message
+        )
+    }
+}
+
+// The policy chain of a proxy, as read from and sent to the policies
+// endpoint.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct PolicyChain {
+    policies_config: Vec<Policy>,
+}
+
+impl PolicyChain {
+    pub fn new(policies: Vec<Policy>) -> Self {
+        Self {
+            policies_config: policies,
+        }
+    }
+
+    pub fn policies(&self) -> &[Policy] {
+        self.policies_config.as_slice()
+    }
+
+    pub fn policies_mut(&mut self) -> &mut Vec<Policy> {
+        &mut self.policies_config
+    }
+
+    pub fn find(&self, name: &str) -> Option<&Policy> {
+        self.policies_config.iter().find(|p| p.name() == name)
+    }
+
+    // Check the configurations of built-in policies against their bundled
+    // schemas.
+    #[cfg(feature = "policy-schemas")]
+    pub fn validate(&self) -> crate::error::Result<()> {
+        validation::validate(self.policies())
+    }
+
+    // Replace the policy chain of a service, validating it first when
+    // schema validation is enabled.
+    #[cfg(feature = "client")]
+    pub fn apply(
+        &self,
+        client: &crate::client::Client,
+        service_id: &str,
+    ) -> crate::error::Result<PolicyChain> {
+        #[cfg(feature = "policy-schemas")]
+        self.validate()?;
+        client.call(&UPDATE, &[service_id], None::<&()>, Some(self))
+    }
+
+    #[cfg(feature = "async-client")]
+    pub async fn apply_async(
+        &self,
+        client: &crate::client::AsyncClient,
+        service_id: &str,
+    ) -> crate::error::Result<PolicyChain> {
+        #[cfg(feature = "policy-schemas")]
+        self.validate()?;
+        client
+            .call(&UPDATE, &[service_id], None::<&()>, Some(self))
+            .await
+    }
+}
+
+impl From<Vec<Policy>> for PolicyChain {
+    fn from(policies: Vec<Policy>) -> Self {
+        Self::new(policies)
+    }
+}
+
+impl From<PolicyChain> for Vec<Policy> {
+    fn from(chain: PolicyChain) -> Self {
+        chain.policies_config
+    }
+}
+
+endpoint! { READ, GET joining [ "/admin/api/services/", "/proxy/policies.json" ] returning PolicyChain }
+endpoint! { UPDATE, PUT joining [ "/admin/api/services/", "/proxy/policies.json" ] returning PolicyChain }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CHAIN: &str = r#"[
+      {
+        "name": "cors",
+        "version": "builtin",
+        "configuration": {
+          "allow_headers": ["Authorization"],
+          "allow_methods": ["GET", "POST"],
+          "allow_origin": "*",
+          "allow_credentials": true
+        },
+        "enabled": true
+      },
+      {
+        "name": "headers",
+        "version": "builtin",
+        "configuration": {
+          "request": [
+            { "op": "set", "header": "X-Request", "value": "{{ service.id }}", "value_type": "liquid" }
+          ]
+        }
+      },
+      {
+        "name": "url_rewriting",
+        "version": "builtin",
+        "configuration": {
+          "commands": [ { "op": "gsub", "regex": "^/v1", "replace": "/", "break": true } ]
+        }
+      },
+      {
+        "name": "rate_limit",
+        "version": "builtin",
+        "configuration": {
+          "fixed_window_limiters": [
+            { "key": { "name": "per_ip", "name_type": "plain", "scope": "service" }, "count": 10, "window": 60 }
+          ],
+          "limits_exceeded_error": { "status_code": 429, "error_handling": "exit" }
+        },
+        "enabled": false
+      },
+      {
+        "name": "ip_check",
+        "version": "builtin",
+        "configuration": { "check_type": "blacklist", "ips": ["10.0.0.0/8"] }
+      },
+      {
+        "name": "routing",
+        "version": "builtin",
+        "configuration": {
+          "rules": [
+            {
+              "url": "https://backend.example.com",
+              "condition": {
+                "operations": [ { "match": "path", "op": "matches", "value": "^/admin" } ]
+              }
+            }
+          ]
+        }
+      },
+      {
+        "name": "apicast",
+        "version": "builtin",
+        "configuration": {}
+      },
+      {
+        "name": "my_policy",
+        "version": "1.0.0",
+        "configuration": { "anything": ["goes"] }
+      }
+    ]"#;
+
+    #[test]
+    fn it_parses_typed_configurations() {
+        let chain = serde_json::from_str::<Vec<Policy>>(CHAIN);
+        assert!(chain.is_ok());
+        let chain = chain.unwrap();
+
+        assert!(matches!(
+            chain[0].configuration(),
+            PolicyConfiguration::Cors(Cors {
+                allow_credentials: Some(true),
+                ..
+            })
+        ));
+        assert!(matches!(
+            chain[1].configuration(),
+            PolicyConfiguration::Headers(_)
+        ));
+        assert!(matches!(
+            chain[2].configuration(),
+            PolicyConfiguration::UrlRewriting(_)
+        ));
+        assert!(matches!(
+            chain[3].configuration(),
+            PolicyConfiguration::RateLimit(_)
+        ));
+        assert!(!chain[3].is_enabled());
+        assert!(matches!(
+            chain[4].configuration(),
+            PolicyConfiguration::IpCheck(IpCheck {
+                check_type: IpCheckType::Blacklist,
+                ..
+            })
+        ));
+        assert!(matches!(
+            chain[5].configuration(),
+            PolicyConfiguration::Routing(_)
+        ));
+        assert!(matches!(
+            chain[6].configuration(),
+            PolicyConfiguration::Other(_)
+        ));
+        assert_eq!(chain[7].name(), "my_policy");
+        assert_eq!(
+            chain[7].configuration(),
+            &PolicyConfiguration::Other(serde_json::json!({ "anything": ["goes"] }))
+        );
+    }
+
+    #[test]
+    fn it_keeps_invalid_builtin_configurations_as_raw_json() {
+        let policy = serde_json::from_str::<Policy>(
+            r#"{ "name": "ip_check", "version": "builtin", "configuration": { "ips": 3 } }"#,
+        )
+        .unwrap();
+        assert!(matches!(
+            policy.configuration(),
+            PolicyConfiguration::Other(_)
+        ));
+    }
+
+    #[test]
+    fn it_keeps_unmodelled_keys_as_raw_json() {
+        let configuration = serde_json::json!({
+            "allow_origin": "*",
+            "allow_methods": ["GET"],
+            "expose_headers": ["X-Trace"]
+        });
+        let policy = Policy::new("cors", BUILTIN, configuration.clone());
+        assert_eq!(
+            policy.configuration(),
+            &PolicyConfiguration::Other(configuration.clone())
+        );
+        let policy = serde_json::from_value::<Policy>(serde_json::to_value(&policy).unwrap());
+        assert_eq!(policy.unwrap().configuration().to_value(), configuration);
+
+        let policy = Policy::new(
+            "ip_check",
+            BUILTIN,
+            serde_json::json!({ "check_type": "greylist", "ips": [] }),
+        );
+        assert_eq!(policy.configuration().to_value()["check_type"], "greylist");
+    }
+
+    #[test]
+    fn it_serializes_back_to_the_wire_format() {
+        let chain = serde_json::from_str::<Vec<Policy>>(CHAIN).unwrap();
+        let value = serde_json::to_value(&chain).unwrap();
+        let original = serde_json::from_str::<Value>(CHAIN).unwrap();
+        assert_eq!(value[0]["configuration"], original[0]["configuration"]);
+        assert_eq!(value[3]["enabled"], false);
+        assert_eq!(value[7], {
+            let mut policy = original[7].clone();
+            policy["enabled"] = true.into();
+            policy
+        });
+
+        let policy = Policy::builtin(PolicyConfiguration::Cors(Cors {
+            allow_origin: Some("*".into()),
+            ..Default::default()
+        }))
+        .unwrap();
+        assert_eq!(
+            serde_json::to_value(&policy).unwrap(),
+            serde_json::json!({
+                "name": "cors",
+                "version": "builtin",
+                "configuration": { "allow_origin": "*" },
+                "enabled": true
+            })
+        );
+    }
+
+    mod read {
+        use super::*;
+
+        endpoint_test! { it_parses, READ, r#"{
+          "policies_config": [
+            {
+              "name": "headers",
+              "version": "builtin",
+              "configuration": {
+                "response": [ { "op": "add", "header": "X-Served-By", "value": "apicast" } ]
+              },
+              "enabled": true
+            },
+            {
+              "name": "apicast",
+              "version": "builtin",
+              "configuration": {},
+              "enabled": true
+            }
+          ]
+        }"# }
+
+        #[test]
+        fn it_finds_policies_by_name() {
+            let chain = READ.parse_str(RESPONSE).unwrap();
+            assert!(matches!(
+                chain.find("headers").map(Policy::configuration),
+                Some(PolicyConfiguration::Headers(_))
+            ));
+            assert!(chain.find("cors").is_none());
+        }
+
+        #[cfg(all(feature = "client", feature = "policy-schemas"))]
+        #[test]
+        fn it_does_not_send_invalid_chains() {
+            use crate::client::test_server::TestServer;
+
+            let (server, client) = TestServer::ok_with_client(RESPONSE);
+            let chain = PolicyChain::new(vec![Policy::new(
+                "ip_check",
+                BUILTIN,
+                serde_json::json!({ "check_type": "greylist", "ips": [] }),
+            )]);
+            assert!(matches!(
+                chain.apply(&client, "1"),
+                Err(crate::Error::InvalidPolicyChain(_))
+            ));
+
+            let chain = READ.parse_str(RESPONSE).unwrap();
+            chain.apply(&client, "1").unwrap();
+            let request = server.received();
+            assert_eq!(request.method, "PUT");
+            assert!(request
+                .body
+                .starts_with(r#"{"policies_config":[{"name":"headers""#));
+        }
+    }
+}
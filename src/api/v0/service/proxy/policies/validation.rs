@@ -0,0 +1,190 @@
+// Offline validation of policy configurations against the JSON schemas of the
+// built-in APIcast policies, as found in their apicast-policy.json manifests.
+//
+// Only built-in policies with a bundled schema are validated. Custom policies
+// are accepted as they are, since their schemas live in the gateway.
+//
+// Configurations are validated in the JSON form they are sent to Porta with,
+// which for configurations read from Porta is the JSON as received.
+use jsonschema::JSONSchema;
+use serde_json::Value;
+use std::sync::OnceLock;
+
+use crate::error::{Error, Result};
+
+pub use super::PolicyViolation;
+
+use super::{Policy, BUILTIN};
+
+const MANIFESTS: &[(&str, &str)] = &[
+    ("apicast", include_str!("schemas/apicast.json")),
+    ("cors", include_str!("schemas/cors.json")),
+    ("headers", include_str!("schemas/headers.json")),
+    ("ip_check", include_str!("schemas/ip_check.json")),
+    ("rate_limit", include_str!("schemas/rate_limit.json")),
+    ("routing", include_str!("schemas/routing.json")),
+    ("url_rewriting", include_str!("schemas/url_rewriting.json")),
+];
+
+type CompiledSchema = std::result::Result<JSONSchema, String>;
+
+// The configuration schemas, compiled on first use.
+fn schemas() -> &'static [(&'static str, CompiledSchema)] {
+    static SCHEMAS: OnceLock<Vec<(&'static str, CompiledSchema)>> = OnceLock::new();
+
+    SCHEMAS.get_or_init(|| {
+        MANIFESTS
+            .iter()
+            .map(|(name, manifest)| {
+                let schema = serde_json::from_str::<Value>(manifest)
+                    .map_err(|e| e.to_string())
+                    .and_then(|manifest| {
+                        JSONSchema::compile(&manifest["configuration"]).map_err(|e| e.to_string())
+                    });
+                (*name, schema)
+            })
+            .collect()
+    })
+}
+
+fn schema(policy: &Policy) -> Option<&'static CompiledSchema> {
+    if policy.version() != BUILTIN {
+        return None;
+    }
+    schemas()
+        .iter()
+        .find(|(name, _)| *name == policy.name())
+        .map(|(_, schema)| schema)
+}
+
+// Whether there is a bundled schema for the policy.
+pub fn has_schema(policy: &Policy) -> bool {
+    schema(policy).is_some()
+}
+
+fn policy_violations(index: usize, policy: &Policy) -> Vec<PolicyViolation> {
+    let violation = |path: String, message: String| PolicyViolation {
+        index,
+        policy: policy.name().to_string(),
+        path,
+        message,
+    };
+
+    let schema = match schema(policy) {
+        Some(Ok(schema)) => schema,
+        Some(Err(e)) => return vec![violation(String::new(), format!("invalid schema: {}", e))],
+        None => return Vec::new(),
+    };
+
+    // the wire form of the policy, so that nothing is validated that is not sent
+    let configuration = serde_json::to_value(policy)
+        .map(|mut policy| policy["configuration"].take())
+        .unwrap_or_default();
+    let Err(errors) = schema.validate(&configuration) else {
+        return Vec::new();
+    };
+    errors
+        .map(|e| violation(e.instance_path.to_string(), e.to_string()))
+        .collect()
+}
+
+// All the violations found in a policy chain.
+pub fn violations(chain: &[Policy]) -> Vec<PolicyViolation> {
+    chain
+        .iter()
+        .enumerate()
+        .flat_map(|(index, policy)| policy_violations(index, policy))
+        .collect()
+}
+
+pub fn validate(chain: &[Policy]) -> Result<()> {
+    let violations = violations(chain);
+    if violations.is_empty() {
+        Ok(())
+    } else {
+        Err(Error::InvalidPolicyChain(violations))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::*;
+    use super::*;
+
+    #[test]
+    fn it_compiles_the_bundled_schemas() {
+        for name in [
+            "apicast",
+            "cors",
+            "headers",
+            "ip_check",
+            "rate_limit",
+            "routing",
+            "url_rewriting",
+        ] {
+            let policy = Policy::new(name, BUILTIN, serde_json::json!({}));
+            let invalid_schema = policy_violations(0, &policy)
+                .into_iter()
+                .filter(|v| v.message().starts_with("invalid schema"))
+                .collect::<Vec<_>>();
+            assert!(invalid_schema.is_empty(), "{:?}", invalid_schema);
+        }
+    }
+
+    #[test]
+    fn it_accepts_valid_chains() {
+        let chain = vec![
+            Policy::builtin(PolicyConfiguration::Cors(Cors {
+                allow_methods: vec!["GET".into()],
+                ..Default::default()
+            }))
+            .unwrap(),
+            Policy::new("apicast", BUILTIN, serde_json::json!({})),
+            Policy::new("custom", "1.0", serde_json::json!({ "anything": 1 })),
+        ];
+        let result = validate(&chain);
+        if let Err(e) = &result {
+            println!("Error: {}", e);
+        }
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn it_validates_configurations_as_received() {
+        let chain = serde_json::from_value::<Vec<Policy>>(serde_json::json!([
+            {
+                "name": "ip_check",
+                "version": "builtin",
+                "configuration": { "check_type": "greylist", "ips": [] }
+            }
+        ]))
+        .unwrap();
+        let violations = violations(&chain);
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].path(), "/check_type");
+        assert!(violations[0].message().contains("greylist"));
+    }
+
+    #[test]
+    fn it_reports_violations_with_their_location() {
+        let chain = vec![
+            Policy::new("apicast", BUILTIN, serde_json::json!({})),
+            Policy::builtin(PolicyConfiguration::Cors(Cors {
+                allow_methods: vec!["FETCH".into()],
+                ..Default::default()
+            }))
+            .unwrap(),
+            Policy::new("ip_check", BUILTIN, serde_json::json!({ "ips": [] })),
+        ];
+        let violations = violations(&chain);
+        assert_eq!(violations.len(), 2);
+        assert_eq!(violations[0].index(), 1);
+        assert_eq!(violations[0].policy(), "cors");
+        assert_eq!(violations[0].path(), "/allow_methods/0");
+        assert_eq!(violations[1].policy(), "ip_check");
+        assert!(matches!(
+            validate(&chain),
+            Err(Error::InvalidPolicyChain(ref v)) if v.len() == 2
+        ));
+    }
+}
@@ -0,0 +1,112 @@
+use serde::{Deserialize, Serialize};
+use straitjacket_macro::straitjacket;
+
+pub type Metadata = crate::resources::Metadata;
+
+// Service plans share their state and parameters with application plans.
+pub use super::plan::{NewPlan, PlanUpdate, State, StateEvent};
+
+#[straitjacket(name_snake = "service_plan")]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Plan {
+    id: u64,
+    name: String,
+    #[serde(rename(serialize = "state_event"))]
+    state: State,
+    setup_fee: f64,
+    cost_per_month: f64,
+    trial_period_days: Option<u64>,
+    cancellation_period: Option<u64>,
+    approval_required: bool,
+    default: Option<bool>,
+    system_name: Option<String>,
+}
+
+impl Plan {
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    pub fn system_name(&self) -> Option<&str> {
+        self.system_name.as_deref()
+    }
+
+    pub fn state(&self) -> State {
+        self.state
+    }
+
+    pub fn is_published(&self) -> bool {
+        self.state == State::Published
+    }
+
+    pub fn is_default(&self) -> bool {
+        self.default.unwrap_or(false)
+    }
+
+    pub fn approval_required(&self) -> bool {
+        self.approval_required
+    }
+
+    pub fn setup_fee(&self) -> f64 {
+        self.setup_fee
+    }
+
+    pub fn cost_per_month(&self) -> f64 {
+        self.cost_per_month
+    }
+}
+
+endpoint! { LIST, GET joining [ "/admin/api/services/", "/service_plans.json" ] returning Plans }
+endpoint! { READ, GET joining [ "/admin/api/services/", "/service_plans/", ".json" ] returning PlanTag }
+endpoint! { CREATE, POST joining [ "/admin/api/services/", "/service_plans.json" ] returning PlanTag }
+endpoint! { UPDATE, PUT joining [ "/admin/api/services/", "/service_plans/", ".json" ] returning PlanTag }
+endpoint! { DELETE, DELETE joining [ "/admin/api/services/", "/service_plans/", ".json" ] returning () }
+// Make the plan the default one for new subscriptions to the service.
+endpoint! { SET_DEFAULT, PUT joining [ "/admin/api/services/", "/service_plans/", "/default.json" ] returning PlanTag }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    endpoint_test! { it_parses, LIST, r#"{
+      "plans": [
+        {
+          "service_plan": {
+            "id": 2357355953035,
+            "name": "Default",
+            "state": "published",
+            "setup_fee": 0.0,
+            "cost_per_month": 0.0,
+            "trial_period_days": null,
+            "cancellation_period": 0,
+            "approval_required": false,
+            "default": true,
+            "created_at": "2019-03-19T09:01:01+00:00",
+            "updated_at": "2019-03-19T09:01:01+00:00",
+            "system_name": "default",
+            "links": [
+              {
+                "rel": "service",
+                "href": "https://istiodevel-admin.3scale.net/admin/api/services/2555417777820"
+              },
+              {
+                "rel": "self",
+                "href": "https://istiodevel-admin.3scale.net/admin/api/services/2555417777820/service_plans/2357355953035"
+              }
+            ]
+          }
+        }
+      ]
+    }"# }
+
+    #[test]
+    fn it_reads_the_default_plan() {
+        let plans: Vec<Plan> = LIST.parse_str(RESPONSE).unwrap().into();
+        assert!(plans[0].is_default());
+        assert!(plans[0].is_published());
+    }
+}
@@ -0,0 +1,507 @@
+use reqwest::{
+    Client as AClient, ClientBuilder, Request as ARequest, RequestBuilder as ARequestBuilder,
+    Response as AResponse,
+};
+use serde::{de::DeserializeOwned, Serialize};
+use std::time::Duration;
+
+use futures_util::stream::{self, Stream};
+
+use crate::error::{Error, Result};
+use crate::resources::http::endpoint::Endpoint;
+use crate::resources::pagination::Paginated;
+
+use super::{
+    parse_response, Method, PageCursor, PageQuery, PlannedRequest, Target, Url, USER_AGENT,
+};
+
+pub type Response = AResponse;
+pub type Request = ARequest;
+pub type RequestBuilder = ARequestBuilder;
+
+// Async counterpart of the blocking client, built on top of reqwest's
+// async API so that it can be driven from within a Tokio runtime.
+pub struct Client {
+    client: AClient,
+    target: Target,
+}
+
+impl Client {
+    fn new_client<D: Into<Option<Duration>>>(timeout: D) -> Result<AClient> {
+        let mut builder = ClientBuilder::new().user_agent(USER_AGENT);
+        // unlike the blocking builder, the async one has no timeout by default
+        // and does not accept an Option
+        if let Some(timeout) = timeout.into() {
+            builder = builder.timeout(timeout);
+        }
+        Ok(builder.build()?)
+    }
+
+    pub fn new<D: Into<Option<Duration>>>(timeout: D) -> Result<Self> {
+        Self::new_host_n_token(None, None, timeout)
+    }
+
+    pub fn new_host_n_token<'h, H, T, D>(host: H, token: T, timeout: D) -> Result<Self>
+    where
+        H: Into<Option<&'h str>>,
+        T: Into<Option<String>>,
+        D: Into<Option<Duration>>,
+    {
+        let target = Target::new(host, token)?;
+        let client = Self::new_client(timeout)?;
+
+        Ok(Self { client, target })
+    }
+
+    pub fn set_host<'h, H>(&mut self, host: H) -> Result<&mut Self>
+    where
+        H: Into<Option<&'h str>>,
+    {
+        self.target.set_host(host)?;
+        Ok(self)
+    }
+
+    pub fn host_url(&self) -> Option<&Url> {
+        self.target.host_url()
+    }
+
+    pub fn host_url_mut(&mut self) -> Option<&mut Url> {
+        self.target.host_url_mut()
+    }
+
+    pub fn host_url_str(&self) -> Option<&str> {
+        self.host_url().map(Url::as_str)
+    }
+
+    pub fn token(&self) -> Option<&str> {
+        self.target.token()
+    }
+
+    pub fn set_token<S>(&mut self, token: S) -> &Self
+    where
+        S: Into<Option<String>>,
+    {
+        self.target.set_token(token);
+        self as &Self
+    }
+
+    pub fn endpoint_request_builder<T, Q, B>(
+        &self,
+        ep: &crate::resources::http::endpoint::Endpoint<'_, '_, T>,
+        args: &[&str],
+        query_string: Option<&Q>,
+        body: Option<&B>,
+    ) -> Result<RequestBuilder>
+    where
+        Q: Serialize + ?Sized,
+        B: Serialize + ?Sized,
+    {
+        let path = ep.path(args)?;
+        // unfortunately request generation needs ownership of http::Method, so need to clone
+        self.request_builder(ep.method().clone(), path.as_str(), query_string, body)
+    }
+
+    pub fn request_builder<Q, B>(
+        &self,
+        method: Method,
+        path: &str,
+        query_string: Option<&Q>,
+        body: Option<&B>,
+    ) -> Result<RequestBuilder>
+    where
+        Q: Serialize + ?Sized,
+        B: Serialize + ?Sized,
+    {
+        let url = self.target.url(path)?;
+        let mut rb = self.client.request(method, url);
+
+        if let Some(qs) = query_string {
+            rb = rb.query(qs);
+        }
+        if let Some(body) = body {
+            rb = rb.json(body);
+        }
+
+        Ok(rb)
+    }
+
+    pub fn endpoint_request<T, Q, B>(
+        &self,
+        ep: &crate::resources::http::endpoint::Endpoint<'_, '_, T>,
+        args: &[&str],
+        query_string: Option<&Q>,
+        body: Option<&B>,
+    ) -> Result<Request>
+    where
+        Q: Serialize + ?Sized,
+        B: Serialize + ?Sized,
+    {
+        let rb = self.endpoint_request_builder(ep, args, query_string, body)?;
+        Ok(rb.build()?)
+    }
+
+    pub fn request<Q, B>(
+        &self,
+        method: Method,
+        path: &str,
+        query_string: Option<&Q>,
+        body: Option<&B>,
+    ) -> Result<Request>
+    where
+        Q: Serialize + ?Sized,
+        B: Serialize + ?Sized,
+    {
+        let rb = self.request_builder(method, path, query_string, body)?;
+        Ok(rb.build()?)
+    }
+
+    pub async fn send_request(&self, request: Request) -> Result<Response> {
+        self.client.execute(request).await.map_err(Error::from)
+    }
+
+    pub async fn send_endpoint<T, Q, B>(
+        &self,
+        ep: &crate::resources::http::endpoint::Endpoint<'_, '_, T>,
+        args: &[&str],
+        query_string: Option<&Q>,
+        body: Option<&B>,
+    ) -> Result<Response>
+    where
+        Q: Serialize + ?Sized,
+        B: Serialize + ?Sized,
+    {
+        self.endpoint_request_builder(ep, args, query_string, body)?
+            .send()
+            .await
+            .map_err(Error::from)
+    }
+
+    // Send the request for an endpoint, check its status and deserialize the
+    // response into the endpoint's type.
+    pub async fn call<M, Q, B>(
+        &self,
+        ep: &crate::resources::http::endpoint::Endpoint<'_, '_, M>,
+        args: &[&str],
+        query_string: Option<&Q>,
+        body: Option<&B>,
+    ) -> Result<M>
+    where
+        M: DeserializeOwned,
+        Q: Serialize + ?Sized,
+        B: Serialize + ?Sized,
+    {
+        let resp = self.send_endpoint(ep, args, query_string, body).await?;
+        let status = resp.status();
+        let body = resp.bytes().await?;
+        parse_response(status, body.as_ref())
+    }
+
+    async fn call_page<M, Q>(
+        &self,
+        method: &Method,
+        path: &str,
+        query_string: Option<&Q>,
+        page: &PageQuery,
+    ) -> Result<M>
+    where
+        M: DeserializeOwned,
+        Q: Serialize + ?Sized,
+    {
+        let resp = self
+            .request_builder(method.clone(), path, query_string, None::<&()>)?
+            .query(page)
+            .send()
+            .await?;
+        let status = resp.status();
+        let body = resp.bytes().await?;
+        parse_response(status, body.as_ref())
+    }
+
+    // Walk all pages of a paginated endpoint, lazily yielding the inner items.
+    // Pages are fetched on demand, `per_page` items at a time (Porta's
+    // maximum if unspecified).
+    pub fn paginate<'a, M, Q, P>(
+        &'a self,
+        ep: &Endpoint<'_, '_, M>,
+        args: &[&str],
+        query_string: Option<&'a Q>,
+        per_page: P,
+    ) -> impl Stream<Item = Result<M::Item>> + 'a
+    where
+        M: Paginated + DeserializeOwned + 'a,
+        Q: Serialize + ?Sized,
+        P: Into<Option<u32>>,
+    {
+        // Resolve the path upfront so that the endpoint needs not outlive the
+        // stream. Errors are reported as its first item.
+        let method = ep.method().clone();
+        let (path, error) = match ep.path(args) {
+            Ok(path) => (path, None),
+            Err(e) => (String::new(), Some(e)),
+        };
+        let state = (PageCursor::new(per_page), Vec::new().into_iter(), error);
+
+        stream::unfold(state, move |(mut cursor, mut items, error)| {
+            let method = method.clone();
+            let path = path.clone();
+            async move {
+                if let Some(e) = error {
+                    cursor.finish();
+                    return Some((Err(e), (cursor, items, None)));
+                }
+
+                loop {
+                    if let Some(item) = items.next() {
+                        return Some((Ok(item), (cursor, items, None)));
+                    }
+
+                    let page = cursor.next_page()?;
+                    match self
+                        .call_page::<M, Q>(&method, path.as_str(), query_string, &page)
+                        .await
+                    {
+                        Ok(response) => {
+                            let page_items: Vec<M::Item> = response.into();
+                            cursor.advance(page_items.len());
+                            items = page_items.into_iter();
+                        }
+                        Err(e) => {
+                            cursor.finish();
+                            return Some((Err(e), (cursor, items, None)));
+                        }
+                    }
+                }
+            }
+        })
+    }
+
+    // Send a planned request, check its status and deserialize the response.
+    pub(crate) async fn call_planned<M: DeserializeOwned>(
+        &self,
+        request: &PlannedRequest,
+    ) -> Result<M> {
+        let resp = self
+            .send(
+                request.method.clone(),
+                &request.path,
+                request.query.as_ref(),
+                request.body.as_ref(),
+            )
+            .await?;
+        let status = resp.status();
+        let body = resp.bytes().await?;
+        parse_response(status, body.as_ref())
+    }
+
+    // Send planned requests in order, stopping at the first failure.
+    pub(crate) async fn send_planned(&self, requests: &[PlannedRequest]) -> Result<()> {
+        for request in requests {
+            self.call_planned::<serde::de::IgnoredAny>(request).await?;
+        }
+        Ok(())
+    }
+
+    pub async fn send<Q, B>(
+        &self,
+        method: Method,
+        path: &str,
+        query_string: Option<&Q>,
+        body: Option<&B>,
+    ) -> Result<Response>
+    where
+        Q: Serialize + ?Sized,
+        B: Serialize + ?Sized,
+    {
+        self.request_builder(method, path, query_string, body)?
+            .send()
+            .await
+            .map_err(Error::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_server::TestServer;
+    use super::*;
+
+    const TOKEN: &str = "a_test_token";
+
+    fn setup_client(server: &TestServer, timeout: u8) -> Client {
+        Client::new_host_n_token(
+            server.url(),
+            TOKEN.to_string(),
+            Duration::from_secs(timeout as u64),
+        )
+        .expect("failed to initalize client")
+    }
+
+    #[test]
+    fn it_generates_a_request_descriptor() {
+        let c = Client::new_host_n_token("https://porta.example.com", TOKEN.to_string(), None)
+            .expect("failed to initalize client");
+        let req = c.request(
+            Method::GET,
+            "/admin/api/services/2555417783508/metrics.json",
+            Some(&[("page", "1")]),
+            None::<&str>,
+        );
+        assert!(req.is_ok());
+        let req = req.unwrap();
+        assert_eq!(
+            req.url().path(),
+            "/admin/api/services/2555417783508/metrics.json"
+        );
+        let query = req.url().query().unwrap_or_default();
+        assert_eq!(query, "access_token=a_test_token&page=1");
+    }
+
+    #[tokio::test]
+    async fn it_gets_a_response() {
+        let server = TestServer::ok("{}");
+        let c = setup_client(&server, 10);
+        let r = c
+            .send(
+                Method::GET,
+                "/admin/api/services/2555417783508/metrics.json",
+                None::<&u8>,
+                None::<&str>,
+            )
+            .await;
+        assert!(r.is_ok());
+        let received = server.received();
+        assert_eq!(received.method, "GET");
+        assert_eq!(
+            received.path_only(),
+            "/admin/api/services/2555417783508/metrics.json"
+        );
+        assert!(received.has_query_pair("access_token", TOKEN));
+    }
+
+    #[tokio::test]
+    async fn it_sends_json_bodies() {
+        let server = TestServer::ok("{}");
+        let c = setup_client(&server, 10);
+        let r = c
+            .send(
+                Method::POST,
+                "/admin/api/services.json",
+                None::<&str>,
+                Some(&serde_json::json!({ "name": "echo-api" })),
+            )
+            .await;
+        assert!(r.is_ok());
+        let received = server.received();
+        assert_eq!(received.method, "POST");
+        assert_eq!(received.body, r#"{"name":"echo-api"}"#);
+    }
+
+    #[tokio::test]
+    async fn it_gets_a_response_when_using_an_endpoint() {
+        let server = TestServer::ok(r#"{ "metrics": [] }"#);
+        let c = setup_client(&server, 10);
+        let endpoint = &crate::api::v0::service::metric::LIST;
+        let resp = c
+            .send_endpoint(endpoint, &["2555417777820"], None::<&str>, None::<&str>)
+            .await;
+        assert!(resp.is_ok());
+        let text = resp.unwrap().text().await;
+        assert!(text.is_ok());
+        assert!(endpoint.parse_str(text.unwrap().as_str()).is_ok());
+        assert_eq!(
+            server.received().path_only(),
+            "/admin/api/services/2555417777820/metrics.json"
+        );
+    }
+
+    #[tokio::test]
+    async fn it_calls_an_endpoint_and_returns_its_type() {
+        let server = TestServer::ok(
+            r#"{
+              "metrics": [
+                {
+                  "metric": {
+                    "id": 2555418191876,
+                    "name": "hits",
+                    "system_name": "hits",
+                    "friendly_name": "Hits",
+                    "description": "Number of API hits",
+                    "unit": "hit"
+                  }
+                }
+              ]
+            }"#,
+        );
+        let c = setup_client(&server, 10);
+        let metrics = c
+            .call(
+                &crate::api::v0::service::metric::LIST,
+                &["2555417777820"],
+                None::<&str>,
+                None::<&str>,
+            )
+            .await;
+        if let Err(e) = &metrics {
+            println!("Error calling endpoint {:#?}", e);
+        }
+        let metrics: Vec<crate::api::v0::service::metric::Metric> = metrics.unwrap().into();
+        assert_eq!(metrics.len(), 1);
+        assert_eq!(metrics[0].system_name(), "hits");
+    }
+
+    #[tokio::test]
+    async fn it_fails_a_call_with_an_error_status() {
+        let server = TestServer::new(vec![(403, r#"{ "error": "Forbidden" }"#.to_string())]);
+        let c = setup_client(&server, 10);
+        let metrics = c
+            .call(
+                &crate::api::v0::service::metric::LIST,
+                &["2555417777820"],
+                None::<&str>,
+                None::<&str>,
+            )
+            .await;
+        assert_eq!(
+            metrics.unwrap_err().status(),
+            Some(http::StatusCode::FORBIDDEN)
+        );
+    }
+
+    fn accounts_page(ids: &[u64]) -> String {
+        let accounts = ids
+            .iter()
+            .map(|id| {
+                format!(
+                    r#"{{ "account": {{ "id": {}, "state": "approved", "org_name": "org{}", "monthly_billing_enabled": true, "monthly_charging_enabled": true, "credit_card_stored": false }} }}"#,
+                    id, id
+                )
+            })
+            .collect::<Vec<_>>();
+        format!(r#"{{ "accounts": [ {} ] }}"#, accounts.join(", "))
+    }
+
+    #[tokio::test]
+    async fn it_streams_all_pages_of_a_paginated_endpoint() {
+        use futures_util::StreamExt;
+
+        let server = TestServer::new(vec![
+            (200, accounts_page(&[1, 2])),
+            (200, accounts_page(&[3])),
+        ]);
+        let c = setup_client(&server, 10);
+        let ids = c
+            .paginate(
+                &crate::api::v0::account::EP_LIST_ACCOUNTS,
+                &[],
+                None::<&str>,
+                2,
+            )
+            .map(|account| account.map(|a| a.id()))
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .collect::<Result<Vec<_>>>();
+        assert_eq!(ids.unwrap(), vec![1, 2, 3]);
+        assert!(server.received().has_query_pair("page", "1"));
+        assert!(server.received().has_query_pair("page", "2"));
+    }
+}
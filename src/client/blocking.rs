@@ -0,0 +1,606 @@
+use reqwest::blocking::{
+    Client as BClient, ClientBuilder, Request as BRequest, RequestBuilder as BRequestBuilder,
+    Response as BResponse,
+};
+use serde::{de::DeserializeOwned, Serialize};
+use std::time::Duration;
+
+use crate::error::{Error, Result};
+use crate::resources::http::endpoint::Endpoint;
+use crate::resources::pagination::Paginated;
+
+use super::{
+    parse_response, Method, PageCursor, PageQuery, PlannedRequest, Target, Url, USER_AGENT,
+};
+
+pub type Response = BResponse;
+pub type Request = BRequest;
+pub type RequestBuilder = BRequestBuilder;
+
+pub struct Client {
+    client: BClient,
+    target: Target,
+}
+
+impl Client {
+    fn new_client<D: Into<Option<Duration>>>(timeout: D) -> Result<BClient> {
+        Ok(ClientBuilder::new()
+            .user_agent(USER_AGENT)
+            .timeout(timeout)
+            .build()?)
+    }
+
+    pub fn new<D: Into<Option<Duration>>>(timeout: D) -> Result<Self> {
+        Self::new_host_n_token(None, None, timeout)
+    }
+
+    pub fn new_host_n_token<'h, H, T, D>(host: H, token: T, timeout: D) -> Result<Self>
+    where
+        H: Into<Option<&'h str>>,
+        T: Into<Option<String>>,
+        D: Into<Option<Duration>>,
+    {
+        let target = Target::new(host, token)?;
+        let client = Self::new_client(timeout)?;
+
+        Ok(Self { client, target })
+    }
+
+    pub fn set_host<'h, H>(&mut self, host: H) -> Result<&mut Self>
+    where
+        H: Into<Option<&'h str>>,
+    {
+        self.target.set_host(host)?;
+        Ok(self)
+    }
+
+    pub fn host_url(&self) -> Option<&Url> {
+        self.target.host_url()
+    }
+
+    pub fn host_url_mut(&mut self) -> Option<&mut Url> {
+        self.target.host_url_mut()
+    }
+
+    pub fn host_url_str(&self) -> Option<&str> {
+        self.host_url().map(Url::as_str)
+    }
+
+    pub fn token(&self) -> Option<&str> {
+        self.target.token()
+    }
+
+    pub fn set_token<S>(&mut self, token: S) -> &Self
+    where
+        S: Into<Option<String>>,
+    {
+        self.target.set_token(token);
+        self as &Self
+    }
+
+    pub fn endpoint_request_builder<T, Q, B>(
+        &self,
+        ep: &crate::resources::http::endpoint::Endpoint<'_, '_, T>,
+        args: &[&str],
+        query_string: Option<&Q>,
+        body: Option<&B>,
+    ) -> Result<RequestBuilder>
+    where
+        Q: Serialize + ?Sized,
+        B: Serialize + ?Sized,
+    {
+        let path = ep.path(args)?;
+        // unfortunately request generation needs ownership of http::Method, so need to clone
+        self.request_builder(ep.method().clone(), path.as_str(), query_string, body)
+    }
+
+    pub fn request_builder<Q, B>(
+        &self,
+        method: Method,
+        path: &str,
+        query_string: Option<&Q>,
+        body: Option<&B>,
+    ) -> Result<RequestBuilder>
+    where
+        Q: Serialize + ?Sized,
+        B: Serialize + ?Sized,
+    {
+        let url = self.target.url(path)?;
+        let mut rb = self.client.request(method, url);
+
+        if let Some(qs) = query_string {
+            rb = rb.query(qs);
+        }
+        if let Some(body) = body {
+            rb = rb.json(body);
+        }
+
+        Ok(rb)
+    }
+
+    pub fn endpoint_request<T, Q, B>(
+        &self,
+        ep: &crate::resources::http::endpoint::Endpoint<'_, '_, T>,
+        args: &[&str],
+        query_string: Option<&Q>,
+        body: Option<&B>,
+    ) -> Result<Request>
+    where
+        Q: Serialize + ?Sized,
+        B: Serialize + ?Sized,
+    {
+        let rb = self.endpoint_request_builder(ep, args, query_string, body)?;
+        Ok(rb.build()?)
+    }
+
+    pub fn request<Q, B>(
+        &self,
+        method: Method,
+        path: &str,
+        query_string: Option<&Q>,
+        body: Option<&B>,
+    ) -> Result<Request>
+    where
+        Q: Serialize + ?Sized,
+        B: Serialize + ?Sized,
+    {
+        let rb = self.request_builder(method, path, query_string, body)?;
+        Ok(rb.build()?)
+    }
+
+    pub fn send_request(&self, request: Request) -> Result<Response> {
+        self.client.execute(request).map_err(Error::from)
+    }
+
+    pub fn send_endpoint<T, Q, B>(
+        &self,
+        ep: &crate::resources::http::endpoint::Endpoint<'_, '_, T>,
+        args: &[&str],
+        query_string: Option<&Q>,
+        body: Option<&B>,
+    ) -> Result<Response>
+    where
+        Q: Serialize + ?Sized,
+        B: Serialize + ?Sized,
+    {
+        self.endpoint_request_builder(ep, args, query_string, body)?
+            .send()
+            .map_err(Error::from)
+    }
+
+    // Send the request for an endpoint, check its status and deserialize the
+    // response into the endpoint's type.
+    pub fn call<M, Q, B>(
+        &self,
+        ep: &crate::resources::http::endpoint::Endpoint<'_, '_, M>,
+        args: &[&str],
+        query_string: Option<&Q>,
+        body: Option<&B>,
+    ) -> Result<M>
+    where
+        M: DeserializeOwned,
+        Q: Serialize + ?Sized,
+        B: Serialize + ?Sized,
+    {
+        let resp = self.send_endpoint(ep, args, query_string, body)?;
+        let status = resp.status();
+        let body = resp.bytes()?;
+        parse_response(status, body.as_ref())
+    }
+
+    fn call_page<M, Q>(
+        &self,
+        method: &Method,
+        path: &str,
+        query_string: Option<&Q>,
+        page: &PageQuery,
+    ) -> Result<M>
+    where
+        M: DeserializeOwned,
+        Q: Serialize + ?Sized,
+    {
+        let resp = self
+            .request_builder(method.clone(), path, query_string, None::<&()>)?
+            .query(page)
+            .send()?;
+        let status = resp.status();
+        let body = resp.bytes()?;
+        parse_response(status, body.as_ref())
+    }
+
+    // Walk all pages of a paginated endpoint, lazily yielding the inner items.
+    // Pages are fetched on demand, `per_page` items at a time (Porta's
+    // maximum if unspecified).
+    pub fn paginate<'a, M, Q, P>(
+        &'a self,
+        ep: &Endpoint<'_, '_, M>,
+        args: &[&str],
+        query_string: Option<&'a Q>,
+        per_page: P,
+    ) -> PaginatedItems<'a, M, Q>
+    where
+        M: Paginated + DeserializeOwned,
+        Q: Serialize + ?Sized,
+        P: Into<Option<u32>>,
+    {
+        // Resolve the path upfront so that the endpoint needs not outlive the
+        // iterator. Errors are reported on the first iteration.
+        let (path, error) = match ep.path(args) {
+            Ok(path) => (path, None),
+            Err(e) => (String::new(), Some(e)),
+        };
+
+        PaginatedItems {
+            client: self,
+            method: ep.method().clone(),
+            path,
+            error,
+            query_string,
+            cursor: PageCursor::new(per_page),
+            items: Vec::new().into_iter(),
+        }
+    }
+
+    // Send a planned request, check its status and deserialize the response.
+    pub(crate) fn call_planned<M: DeserializeOwned>(&self, request: &PlannedRequest) -> Result<M> {
+        let resp = self.send(
+            request.method.clone(),
+            &request.path,
+            request.query.as_ref(),
+            request.body.as_ref(),
+        )?;
+        let status = resp.status();
+        let body = resp.bytes()?;
+        parse_response(status, body.as_ref())
+    }
+
+    // Send planned requests in order, stopping at the first failure.
+    pub(crate) fn send_planned(&self, requests: &[PlannedRequest]) -> Result<()> {
+        for request in requests {
+            self.call_planned::<serde::de::IgnoredAny>(request)?;
+        }
+        Ok(())
+    }
+
+    pub fn send<Q, B>(
+        &self,
+        method: Method,
+        path: &str,
+        query_string: Option<&Q>,
+        body: Option<&B>,
+    ) -> Result<Response>
+    where
+        Q: Serialize + ?Sized,
+        B: Serialize + ?Sized,
+    {
+        self.request_builder(method, path, query_string, body)?
+            .send()
+            .map_err(Error::from)
+    }
+}
+
+pub struct PaginatedItems<'a, M: Paginated, Q: ?Sized> {
+    client: &'a Client,
+    method: Method,
+    path: String,
+    error: Option<Error>,
+    query_string: Option<&'a Q>,
+    cursor: PageCursor,
+    items: std::vec::IntoIter<M::Item>,
+}
+
+impl<'a, M, Q> Iterator for PaginatedItems<'a, M, Q>
+where
+    M: Paginated + DeserializeOwned,
+    Q: Serialize + ?Sized,
+{
+    type Item = Result<M::Item>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(e) = self.error.take() {
+            self.cursor.finish();
+            return Some(Err(e));
+        }
+
+        loop {
+            if let Some(item) = self.items.next() {
+                return Some(Ok(item));
+            }
+
+            let page = self.cursor.next_page()?;
+            match self.client.call_page::<M, Q>(
+                &self.method,
+                self.path.as_str(),
+                self.query_string,
+                &page,
+            ) {
+                Ok(response) => {
+                    let items: Vec<M::Item> = response.into();
+                    self.cursor.advance(items.len());
+                    self.items = items.into_iter();
+                }
+                Err(e) => {
+                    self.cursor.finish();
+                    return Some(Err(e));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_server::TestServer;
+    use super::*;
+
+    const TOKEN: &str = "a_test_token";
+
+    const SERVICES: &str = r#"{
+      "services": [
+        {
+          "service": {
+            "id": 2555417777820,
+            "name": "echo-api",
+            "state": "incomplete",
+            "system_name": "echo-api",
+            "backend_version": "1",
+            "deployment_option": "self_managed",
+            "support_email": "joaquim@redhat.com",
+            "description": "Echo API",
+            "intentions_required": false,
+            "buyers_manage_apps": true,
+            "buyers_manage_keys": true,
+            "referrer_filters_required": false,
+            "custom_keys_enabled": true,
+            "buyer_key_regenerate_enabled": true,
+            "mandatory_app_key": true,
+            "buyer_can_select_plan": false,
+            "buyer_plan_change_permission": "request",
+            "created_at": "2019-03-19T09:01:01+00:00",
+            "updated_at": "2020-04-05T22:44:57+01:00",
+            "links": [
+              {
+                "rel": "metrics",
+                "href": "https://istiodevel-admin.3scale.net/admin/api/services/2555417777820/metrics"
+              }
+            ]
+          }
+        }
+      ]
+    }"#;
+
+    fn setup_client(server: &TestServer, timeout: u8) -> Client {
+        Client::new_host_n_token(
+            server.url(),
+            TOKEN.to_string(),
+            Duration::from_secs(timeout as u64),
+        )
+        .expect("failed to initalize client")
+    }
+
+    #[test]
+    fn it_generates_a_request_descriptor() {
+        let c = Client::new_host_n_token("https://porta.example.com", TOKEN.to_string(), None)
+            .expect("failed to initalize client");
+        let req = c.request(
+            Method::GET,
+            "/admin/api/services/2555417783508/metrics.json",
+            None::<&str>,
+            None::<&str>,
+        );
+        assert!(req.is_ok());
+        let req = req.unwrap();
+        assert_eq!(
+            req.url().path(),
+            "/admin/api/services/2555417783508/metrics.json"
+        );
+        assert!(req
+            .url()
+            .query_pairs()
+            .any(|(k, v)| k == "access_token" && v == TOKEN));
+    }
+
+    #[test]
+    fn it_fails_to_generate_a_request_without_a_host() {
+        let c = Client::new(None).expect("failed to initalize client");
+        let req = c.request(
+            Method::GET,
+            "/admin/api/services.json",
+            None::<&str>,
+            None::<&str>,
+        );
+        assert!(matches!(req, Err(Error::NoHost)));
+    }
+
+    #[test]
+    fn it_gets_a_response() {
+        let server = TestServer::ok("{}");
+        let c = setup_client(&server, 10);
+        let r = c.send(
+            Method::GET,
+            "/admin/api/services/2555417783508/metrics.json",
+            None::<&u8>,
+            None::<&str>,
+        );
+        assert!(r.is_ok());
+        let received = server.received();
+        assert_eq!(received.method, "GET");
+        assert_eq!(
+            received.path_only(),
+            "/admin/api/services/2555417783508/metrics.json"
+        );
+        assert!(received.has_query_pair("access_token", TOKEN));
+    }
+
+    #[test]
+    fn it_sends_query_strings_and_json_bodies() {
+        let server = TestServer::ok("{}");
+        let c = setup_client(&server, 10);
+        let r = c.send(
+            Method::POST,
+            "/admin/api/services.json",
+            Some(&[("page", "2")]),
+            Some(&serde_json::json!({ "name": "echo-api" })),
+        );
+        assert!(r.is_ok());
+        let received = server.received();
+        assert_eq!(received.method, "POST");
+        assert!(received.has_query_pair("page", "2"));
+        assert_eq!(received.body, r#"{"name":"echo-api"}"#);
+    }
+
+    #[test]
+    fn it_returns_a_request_builder_with_endpoint_types() {
+        let server = TestServer::ok(SERVICES);
+        let c = setup_client(&server, 10);
+        let endpoint = &crate::api::v0::service::LIST;
+        let rb = c.endpoint_request_builder(endpoint, &[], None::<&str>, None::<&str>);
+        if let Err(e) = &rb {
+            println!("Error generating RequestBuilder {:#?}", e);
+        }
+        assert!(rb.is_ok());
+    }
+
+    #[test]
+    fn it_returns_a_request_with_endpoint_types() {
+        let server = TestServer::ok(SERVICES);
+        let c = setup_client(&server, 10);
+        let endpoint = &crate::api::v0::service::LIST;
+        let req = c.endpoint_request(endpoint, &[], None::<&str>, None::<&str>);
+        if let Err(e) = &req {
+            println!("Error generating RequestBuilder {:#?}", e);
+        }
+        assert!(req.is_ok());
+    }
+
+    #[test]
+    fn it_gets_a_response_when_using_an_endpoint() {
+        let server = TestServer::ok(SERVICES);
+        let c = setup_client(&server, 10);
+        let endpoint = &crate::api::v0::service::LIST;
+        let resp = c.send_endpoint(endpoint, &[], None::<&str>, None::<&str>);
+        assert!(resp.is_ok());
+        let resp = resp.unwrap();
+        let text = resp.text();
+        assert!(text.is_ok());
+        assert!(endpoint.parse_str(text.unwrap().as_str()).is_ok());
+        assert_eq!(server.received().path_only(), "/admin/api/services.json");
+    }
+
+    #[test]
+    fn it_calls_an_endpoint_and_returns_its_type() {
+        let server = TestServer::ok(SERVICES);
+        let c = setup_client(&server, 10);
+        let services = c.call(
+            &crate::api::v0::service::LIST,
+            &[],
+            None::<&str>,
+            None::<&str>,
+        );
+        if let Err(e) = &services {
+            println!("Error calling endpoint {:#?}", e);
+        }
+        let services: Vec<crate::api::v0::service::Service> = services.unwrap().into();
+        assert_eq!(services.len(), 1);
+        assert_eq!(services[0].id(), 2555417777820);
+    }
+
+    #[test]
+    fn it_fails_a_call_with_an_error_status() {
+        let server = TestServer::new(vec![(404, r#"{ "status": "Not found" }"#.to_string())]);
+        let c = setup_client(&server, 10);
+        let services = c.call(
+            &crate::api::v0::service::LIST,
+            &[],
+            None::<&str>,
+            None::<&str>,
+        );
+        let err = services.unwrap_err();
+        assert!(err.is_not_found());
+        assert!(err.body().unwrap().contains("Not found"));
+    }
+
+    #[test]
+    fn it_calls_an_endpoint_with_an_empty_response() {
+        endpoint! { EP_EMPTY, DELETE joining [ "/admin/api/services/", ".json" ] returning () }
+
+        let server = TestServer::ok("");
+        let c = setup_client(&server, 10);
+        let r = c.call(&EP_EMPTY, &["2555417777820"], None::<&str>, None::<&str>);
+        assert!(r.is_ok());
+        let received = server.received();
+        assert_eq!(received.method, "DELETE");
+        assert_eq!(
+            received.path_only(),
+            "/admin/api/services/2555417777820.json"
+        );
+    }
+
+    fn accounts_page(ids: &[u64]) -> String {
+        let accounts = ids
+            .iter()
+            .map(|id| {
+                format!(
+                    r#"{{ "account": {{ "id": {}, "state": "approved", "org_name": "org{}", "monthly_billing_enabled": true, "monthly_charging_enabled": true, "credit_card_stored": false }} }}"#,
+                    id, id
+                )
+            })
+            .collect::<Vec<_>>();
+        format!(r#"{{ "accounts": [ {} ] }}"#, accounts.join(", "))
+    }
+
+    #[test]
+    fn it_walks_all_pages_of_a_paginated_endpoint() {
+        let server = TestServer::new(vec![
+            (200, accounts_page(&[1, 2])),
+            (200, accounts_page(&[3, 4])),
+            (200, accounts_page(&[5])),
+        ]);
+        let c = setup_client(&server, 10);
+        let ids = c
+            .paginate(
+                &crate::api::v0::account::EP_LIST_ACCOUNTS,
+                &[],
+                Some(&[("state", "approved")]),
+                2,
+            )
+            .map(|account| account.map(|a| a.id()))
+            .collect::<Result<Vec<_>>>();
+        assert_eq!(ids.unwrap(), vec![1, 2, 3, 4, 5]);
+
+        for page in 1..=3 {
+            let received = server.received();
+            assert_eq!(received.path_only(), "/admin/api/accounts.json");
+            assert!(received.has_query_pair("page", &page.to_string()));
+            assert!(received.has_query_pair("per_page", "2"));
+            assert!(received.has_query_pair("state", "approved"));
+        }
+    }
+
+    #[test]
+    fn it_stops_paginating_after_an_error() {
+        let server = TestServer::new(vec![(200, accounts_page(&[1, 2])), (500, "{}".to_string())]);
+        let c = setup_client(&server, 10);
+        let mut accounts = c.paginate(
+            &crate::api::v0::account::EP_LIST_ACCOUNTS,
+            &[],
+            None::<&str>,
+            2,
+        );
+        assert!(accounts.next().unwrap().is_ok());
+        assert!(accounts.next().unwrap().is_ok());
+        assert!(accounts.next().unwrap().is_err());
+        assert!(accounts.next().is_none());
+    }
+
+    #[test]
+    fn it_drops_the_token_when_changing_hosts() {
+        let mut c = Client::new_host_n_token("https://porta.example.com", TOKEN.to_string(), None)
+            .expect("failed to initalize client");
+        assert_eq!(c.token(), Some(TOKEN));
+        c.set_host("https://other.example.com")
+            .expect("failed to set host");
+        assert_eq!(c.token(), None);
+        assert_eq!(c.host_url_str(), Some("https://other.example.com/"));
+    }
+}
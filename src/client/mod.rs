@@ -1,315 +1,90 @@
-use reqwest::blocking::{
-    Client as BClient, ClientBuilder, Request as BRequest, RequestBuilder as BRequestBuilder,
-    Response as BResponse,
-};
-use serde::Serialize;
-use std::error::Error;
-use std::time::Duration;
+//! HTTP clients for Porta's admin API.
+//!
+//! The `client` feature provides the blocking `Client`, and the `async-client`
+//! feature provides `AsyncClient`. Both share host, token and URL handling.
+
+use serde::de::DeserializeOwned;
+
+use crate::error::{Error, Result};
+use crate::resources::http::endpoint;
 
 static USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
 
 pub type Url = reqwest::Url;
-pub type Response = BResponse;
-pub type Request = BRequest;
-pub type RequestBuilder = BRequestBuilder;
 pub type Method = reqwest::Method;
 
-pub struct Client {
-    client: BClient,
-    token: Option<String>,
-    host_url: Option<Url>,
+mod pagination;
+mod target;
+use pagination::{PageCursor, PageQuery};
+use target::Target;
+
+// Check the status of a response and deserialize its body into the type the
+// endpoint returns. Shared by both client flavours.
+fn parse_response<M: DeserializeOwned>(status: reqwest::StatusCode, body: &[u8]) -> Result<M> {
+    if !status.is_success() {
+        return Err(Error::Status {
+            status,
+            body: String::from_utf8_lossy(body).into_owned(),
+        });
+    }
+
+    // Some endpoints (ie. deletions) reply with an empty body.
+    let body = if body.iter().all(u8::is_ascii_whitespace) {
+        b"null".as_ref()
+    } else {
+        body
+    };
+
+    endpoint::parse_reader(body)
 }
 
-impl Client {
-    fn parse_url(url: &str) -> Result<Url, Box<dyn Error>> {
-        let url = url.parse::<Url>().map_err(Box::new)?;
-        Ok(url)
-    }
-
-    // Check that a URL is well-formed if present.
-    fn to_option_url<'h, H: Into<Option<&'h str>>>(host: H) -> Result<Option<Url>, Box<dyn Error>> {
-        Ok(match host.into() {
-            Some(url) => Some(Self::parse_url(url)?),
-            None => None,
-        })
-    }
-
-    fn new_client<D: Into<Option<Duration>>>(timeout: D) -> Result<BClient, Box<dyn Error>> {
-        Ok(ClientBuilder::new()
-            .user_agent(USER_AGENT)
-            .timeout(timeout)
-            .build()
-            .map_err(Box::new)?)
-    }
-
-    pub fn new<D: Into<Option<Duration>>>(timeout: D) -> Result<Self, Box<dyn Error>> {
-        Self::new_host_n_token(None, None, timeout)
-    }
-
-    pub fn new_host_n_token<'h, H, T, D>(
-        host: H,
-        token: T,
-        timeout: D,
-    ) -> Result<Self, Box<dyn Error>>
-    where
-        H: Into<Option<&'h str>>,
-        T: Into<Option<String>>,
-        D: Into<Option<Duration>>,
-    {
-        let host_url = Self::to_option_url(host)?;
-        let client = Self::new_client(timeout)?;
-
-        Ok(Self {
-            client,
-            token: token.into(),
-            host_url,
-        })
-    }
-
-    pub fn set_host<'h, H>(&mut self, host: H) -> Result<&mut Self, Box<dyn Error>>
-    where
-        H: Into<Option<&'h str>>,
-    {
-        self.host_url = Self::to_option_url(host)?;
-        // remove the token, if any
-        self.token = None;
-        Ok(self)
-    }
-
-    pub fn host_url(&self) -> Option<&Url> {
-        self.host_url.as_ref()
-    }
-
-    pub fn host_url_mut(&mut self) -> Option<&mut Url> {
-        self.host_url.as_mut()
-    }
-
-    pub fn host_url_str(&self) -> Option<&str> {
-        self.host_url().map(Url::as_str)
-    }
-
-    pub fn token(&self) -> Option<&str> {
-        self.token.as_ref().map(|s| s.as_str())
-    }
-
-    pub fn set_token<S>(&mut self, token: S) -> &Self
-    where
-        S: Into<Option<String>>,
-    {
-        self.token = token.into();
-        self as &Self
-    }
-
-    fn host_url_result(&self) -> Result<&Url, Box<dyn Error>> {
-        self.host_url().ok_or_else(|| From::from("no url"))
-    }
+// A request resolved ahead of sending, so that the changes of a plan are
+// described once and sent with either client.
+pub(crate) struct PlannedRequest {
+    method: Method,
+    path: String,
+    query: Option<serde_json::Value>,
+    body: Option<serde_json::Value>,
+}
 
-    pub fn endpoint_request_builder<T, Q, B>(
-        &self,
-        ep: &crate::resources::http::endpoint::Endpoint<'_, '_, T>,
+impl PlannedRequest {
+    pub(crate) fn new<M, Q, B>(
+        ep: &endpoint::Endpoint<'_, '_, M>,
         args: &[&str],
         query_string: Option<&Q>,
         body: Option<&B>,
-    ) -> Result<RequestBuilder, Box<dyn Error>>
+    ) -> Result<Self>
     where
-        Q: Serialize + ?Sized,
-        B: Serialize + ?Sized,
+        Q: serde::Serialize + ?Sized,
+        B: serde::Serialize + ?Sized,
     {
-        let path = ep.path(args)?;
-        // unfortunately request generation needs ownership of http::Method, so need to clone
-        self.request_builder(ep.method().clone(), path.as_str(), query_string, body)
-    }
-
-    pub fn request_builder<Q, B>(
-        &self,
-        method: Method,
-        path: &str,
-        query_string: Option<&Q>,
-        body: Option<&B>,
-    ) -> Result<RequestBuilder, Box<dyn Error>>
-    where
-        Q: Serialize + ?Sized,
-        B: Serialize + ?Sized,
-    {
-        // Option#ok_or_else() needs inference of the (E)rror and (F)nOnce
-        // parameters, and when using it in some scenarios where inference is
-        // not possible, ie. without fixing the types via a return type or
-        // using it with Try and a boxed trait, then it needs the explicit
-        // specification of both generic types - however you can't just specify
-        // E, you also must do so with F, but F is a closure, which is
-        // unnameable. We can't use a trait object either (ie. dyn FnOnce),
-        // since that's not the bound of F, so the solution is to either:
-        //
-        // 1. Use a helper that solves inference via the explicit return type
-        // 2. Specify both types... using a _ for the closure.
-        // 3. Use a cast:
-        // let url = (self.host_url.as_ref().ok_or_else(|| {
-        //         From::from("no url")
-        //     }) as Result<&Url, Box<dyn Error>>)?;
-        let url = self.host_url_result()?.join(path).map_err(Box::new)?;
-        let mut rb = self.client.request(method, url);
-
-        if let Some(ref token) = self.token {
-            rb = rb.query(&[("access_token", token.as_str())]);
-        }
-        if let Some(qs) = query_string {
-            rb = rb.query(qs);
-        }
-        if let Some(body) = body {
-            rb = rb.json(body);
+        fn to_value<T: serde::Serialize + ?Sized>(
+            value: Option<&T>,
+        ) -> Result<Option<serde_json::Value>> {
+            value
+                .map(serde_json::to_value)
+                .transpose()
+                .map_err(Error::Serialization)
         }
 
-        Ok(rb)
-    }
-
-    pub fn endpoint_request<T, Q, B>(
-        &self,
-        ep: &crate::resources::http::endpoint::Endpoint<'_, '_, T>,
-        args: &[&str],
-        query_string: Option<&Q>,
-        body: Option<&B>,
-    ) -> Result<Request, Box<dyn Error>>
-    where
-        Q: Serialize + ?Sized,
-        B: Serialize + ?Sized,
-    {
-        let rb = self.endpoint_request_builder(ep, args, query_string, body)?;
-        rb.build().map_err(|e| Box::new(e) as Box<dyn Error>)
-    }
-
-    pub fn request<Q, B>(
-        &self,
-        method: Method,
-        path: &str,
-        query_string: Option<&Q>,
-        body: Option<&B>,
-    ) -> Result<Request, Box<dyn Error>>
-    where
-        Q: Serialize + ?Sized,
-        B: Serialize + ?Sized,
-    {
-        let rb = self.request_builder(method, path, query_string, body)?;
-        rb.build().map_err(|e| Box::new(e) as Box<dyn Error>)
-    }
-
-    pub fn send_request(&self, request: Request) -> Result<Response, Box<dyn Error>> {
-        self.client
-            .execute(request)
-            .map_err(|e| Box::new(e) as Box<dyn Error>)
-    }
-
-    pub fn send_endpoint<T, Q, B>(
-        &self,
-        ep: &crate::resources::http::endpoint::Endpoint<'_, '_, T>,
-        args: &[&str],
-        query_string: Option<&Q>,
-        body: Option<&B>,
-    ) -> Result<Response, Box<dyn Error>>
-    where
-        Q: Serialize + ?Sized,
-        B: Serialize + ?Sized,
-    {
-        self.endpoint_request_builder(ep, args, query_string, body)?
-            .send()
-            .map_err(|e| Box::new(e) as Box<dyn Error>)
-    }
-
-    pub fn send<Q, B>(
-        &self,
-        method: Method,
-        path: &str,
-        query_string: Option<&Q>,
-        body: Option<&B>,
-    ) -> Result<Response, Box<dyn Error>>
-    where
-        Q: Serialize + ?Sized,
-        B: Serialize + ?Sized,
-    {
-        self.request_builder(method, path, query_string, body)?
-            .send()
-            .map_err(|e| Box::new(e) as Box<dyn Error>)
+        Ok(Self {
+            method: ep.method().clone(),
+            path: ep.path(args)?,
+            query: to_value(query_string)?,
+            body: to_value(body)?,
+        })
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    mod env {
-        use super::*;
-
-        fn get_host_and_token_from_env() -> (String, String) {
-            let (host, token) = (std::env::var("PORTA_URL"), std::env::var("ACCESS_TOKEN"));
-            assert!(host.is_ok() && token.is_ok(), "You need to set the environment variables PORTA_URL and ACCESS_TOKEN for integration tests to run.");
-            (host.unwrap(), token.unwrap())
-        }
-
-        pub fn setup_client(timeout: u8) -> Client {
-            let (host, token) = get_host_and_token_from_env();
-            Client::new_host_n_token(host.as_str(), token, Duration::from_secs(timeout as u64))
-                .expect("failed to initalize client")
-        }
-    }
-
-    use env::setup_client;
+#[cfg(feature = "client")]
+pub mod blocking;
+#[cfg(feature = "client")]
+pub use blocking::{Client, Request, RequestBuilder, Response};
 
-    #[test]
-    fn it_generates_a_request_descriptor() {
-        let c = setup_client(10);
-        let req = c.request(
-            Method::GET,
-            "/admin/api/services/2555417783508/metrics.json",
-            None::<&str>,
-            None::<&str>,
-        );
-        assert!(req.is_ok());
-    }
+#[cfg(feature = "async-client")]
+pub mod async_client;
+#[cfg(feature = "async-client")]
+pub use async_client::Client as AsyncClient;
 
-    #[test]
-    fn it_gets_a_response() {
-        let c = setup_client(10);
-        let r = c.send(
-            Method::GET,
-            "/admin/api/services/2555417783508/metrics.json",
-            None::<&u8>,
-            None::<&str>,
-        );
-        assert!(r.is_ok());
-    }
-
-    #[test]
-    fn it_returns_a_request_builder_with_endpoint_types() {
-        let c = setup_client(10);
-        let endpoint = &crate::api::v0::service::LIST;
-        let rb = c.endpoint_request_builder(endpoint, &[], None::<&str>, None::<&str>);
-        match &rb {
-            Err(e) => println!("Error generating RequestBuilder {:#?}", e),
-            _ => (),
-        }
-        assert!(rb.is_ok());
-    }
-
-    #[test]
-    fn it_returns_a_request_with_endpoint_types() {
-        let c = setup_client(10);
-        let endpoint = &crate::api::v0::service::LIST;
-        let req = c.endpoint_request(endpoint, &[], None::<&str>, None::<&str>);
-        match &req {
-            Err(e) => println!("Error generating RequestBuilder {:#?}", e),
-            _ => (),
-        }
-        assert!(req.is_ok());
-    }
-
-    #[test]
-    fn it_gets_a_response_when_using_an_endpoint() {
-        let c = setup_client(10);
-        let endpoint = &crate::api::v0::service::LIST;
-        let resp = c.send_endpoint(endpoint, &[], None::<&str>, None::<&str>);
-        assert!(resp.is_ok());
-        let resp = resp.unwrap();
-        let text = resp.text();
-        assert!(text.is_ok());
-        assert!(endpoint.parse_str(text.unwrap().as_str()).is_ok());
-    }
-}
+#[cfg(test)]
+pub(crate) mod test_server;
@@ -0,0 +1,87 @@
+use serde::Serialize;
+
+use crate::resources::pagination::MAX_PER_PAGE;
+
+// Query parameters selecting a page of a paginated endpoint.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub(super) struct PageQuery {
+    page: u32,
+    per_page: u32,
+}
+
+// Keeps track of which page to request next, shared by the blocking iterator
+// and the async stream.
+#[derive(Debug, Clone)]
+pub(super) struct PageCursor {
+    next: Option<u32>,
+    per_page: u32,
+}
+
+impl PageCursor {
+    pub fn new<P: Into<Option<u32>>>(per_page: P) -> Self {
+        let per_page = per_page
+            .into()
+            .unwrap_or(MAX_PER_PAGE)
+            .clamp(1, MAX_PER_PAGE);
+
+        Self {
+            next: Some(1),
+            per_page,
+        }
+    }
+
+    // The query for the next page to fetch, if any is left.
+    pub fn next_page(&self) -> Option<PageQuery> {
+        self.next.map(|page| PageQuery {
+            page,
+            per_page: self.per_page,
+        })
+    }
+
+    // Record how many items the last fetched page had. A short page is the last one.
+    pub fn advance(&mut self, items: usize) {
+        self.next = match self.next {
+            Some(page) if items >= self.per_page as usize => Some(page + 1),
+            _ => None,
+        };
+    }
+
+    pub fn finish(&mut self) {
+        self.next = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_stops_after_a_short_page() {
+        let mut cursor = PageCursor::new(2);
+        assert_eq!(
+            cursor.next_page(),
+            Some(PageQuery {
+                page: 1,
+                per_page: 2
+            })
+        );
+        cursor.advance(2);
+        assert_eq!(
+            cursor.next_page(),
+            Some(PageQuery {
+                page: 2,
+                per_page: 2
+            })
+        );
+        cursor.advance(1);
+        assert_eq!(cursor.next_page(), None);
+    }
+
+    #[test]
+    fn it_caps_the_page_size() {
+        let cursor = PageCursor::new(10_000);
+        assert_eq!(cursor.next_page().unwrap().per_page, MAX_PER_PAGE);
+        let cursor = PageCursor::new(None);
+        assert_eq!(cursor.next_page().unwrap().per_page, MAX_PER_PAGE);
+    }
+}
@@ -0,0 +1,84 @@
+use crate::error::{Error, Result};
+
+use super::Url;
+
+// The Porta instance a client talks to: host URL plus optional access token.
+//
+// This is shared by both client flavours so that URL construction and token
+// handling behave identically regardless of the transport.
+#[derive(Debug, Clone, Default)]
+pub(super) struct Target {
+    host_url: Option<Url>,
+    token: Option<String>,
+}
+
+impl Target {
+    fn parse_url(url: &str) -> Result<Url> {
+        let url = url.parse::<Url>()?;
+        Ok(url)
+    }
+
+    // Check that a URL is well-formed if present.
+    fn to_option_url<'h, H: Into<Option<&'h str>>>(host: H) -> Result<Option<Url>> {
+        Ok(match host.into() {
+            Some(url) => Some(Self::parse_url(url)?),
+            None => None,
+        })
+    }
+
+    pub fn new<'h, H, T>(host: H, token: T) -> Result<Self>
+    where
+        H: Into<Option<&'h str>>,
+        T: Into<Option<String>>,
+    {
+        Ok(Self {
+            host_url: Self::to_option_url(host)?,
+            token: token.into(),
+        })
+    }
+
+    pub fn set_host<'h, H>(&mut self, host: H) -> Result<()>
+    where
+        H: Into<Option<&'h str>>,
+    {
+        self.host_url = Self::to_option_url(host)?;
+        // remove the token, if any
+        self.token = None;
+        Ok(())
+    }
+
+    pub fn host_url(&self) -> Option<&Url> {
+        self.host_url.as_ref()
+    }
+
+    pub fn host_url_mut(&mut self) -> Option<&mut Url> {
+        self.host_url.as_mut()
+    }
+
+    pub fn token(&self) -> Option<&str> {
+        self.token.as_deref()
+    }
+
+    pub fn set_token<S>(&mut self, token: S)
+    where
+        S: Into<Option<String>>,
+    {
+        self.token = token.into();
+    }
+
+    fn host_url_result(&self) -> Result<&Url> {
+        self.host_url().ok_or(Error::NoHost)
+    }
+
+    // Full URL for a path on the target host, carrying the access token if any.
+    pub fn url(&self, path: &str) -> Result<Url> {
+        let mut url = self.host_url_result()?.join(path)?;
+
+        if let Some(ref token) = self.token {
+            url.query_pairs_mut()
+                .append_pair("access_token", token.as_str());
+        }
+
+        Ok(url)
+    }
+}
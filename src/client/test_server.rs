@@ -0,0 +1,132 @@
+// A tiny stand-in for Porta, so that client tests don't need a live instance.
+//
+// The server answers each incoming connection with the next canned response
+// and records what it was asked, then shuts down once all responses are used.
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+
+#[derive(Debug, Clone)]
+pub struct ReceivedRequest {
+    pub method: String,
+    pub path: String,
+    pub body: String,
+}
+
+impl ReceivedRequest {
+    // Path without the query string.
+    pub fn path_only(&self) -> &str {
+        self.path.split('?').next().unwrap_or_default()
+    }
+
+    pub fn has_query_pair(&self, key: &str, value: &str) -> bool {
+        let query = self
+            .path
+            .split_once('?')
+            .map(|(_, q)| q)
+            .unwrap_or_default();
+        url::form_urlencoded::parse(query.as_bytes()).any(|(k, v)| k == key && v == value)
+    }
+}
+
+pub struct TestServer {
+    url: String,
+    requests: Receiver<ReceivedRequest>,
+}
+
+impl TestServer {
+    pub fn new(responses: Vec<(u16, String)>) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("failed to bind test server");
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let (tx, rx) = mpsc::channel();
+
+        thread::spawn(move || {
+            for (status, body) in responses {
+                let (stream, _) = match listener.accept() {
+                    Ok(conn) => conn,
+                    Err(_) => return,
+                };
+                let mut reader = BufReader::new(stream);
+                let request = match Self::read_request(&mut reader) {
+                    Some(request) => request,
+                    None => return,
+                };
+                let mut stream = reader.into_inner();
+                let _ = write!(
+                    stream,
+                    "HTTP/1.1 {} Test\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                );
+                let _ = stream.flush();
+                if tx.send(request).is_err() {
+                    return;
+                }
+            }
+        });
+
+        Self { url, requests: rx }
+    }
+
+    pub fn ok(body: &str) -> Self {
+        Self::new(vec![(200, body.to_string())])
+    }
+
+    // A server with the given responses and a blocking client pointed at it.
+    #[cfg(feature = "client")]
+    pub fn with_client(responses: Vec<(u16, String)>) -> (Self, crate::client::Client) {
+        let server = Self::new(responses);
+        let client = crate::client::Client::new_host_n_token(server.url(), None, None)
+            .expect("failed to create test client");
+        (server, client)
+    }
+
+    #[cfg(feature = "client")]
+    pub fn ok_with_client(body: &str) -> (Self, crate::client::Client) {
+        Self::with_client(vec![(200, body.to_string())])
+    }
+
+    pub fn url(&self) -> &str {
+        self.url.as_str()
+    }
+
+    pub fn received(&self) -> ReceivedRequest {
+        self.requests
+            .recv_timeout(std::time::Duration::from_secs(5))
+            .expect("the test server did not receive a request")
+    }
+
+    fn read_request(reader: &mut impl BufRead) -> Option<ReceivedRequest> {
+        let mut request_line = String::new();
+        reader.read_line(&mut request_line).ok()?;
+        let mut parts = request_line.split_whitespace();
+        let method = parts.next()?.to_string();
+        let path = parts.next()?.to_string();
+
+        let mut content_length = 0;
+        loop {
+            let mut line = String::new();
+            reader.read_line(&mut line).ok()?;
+            let line = line.trim_end();
+            if line.is_empty() {
+                break;
+            }
+            if let Some((name, value)) = line.split_once(':') {
+                if name.eq_ignore_ascii_case("content-length") {
+                    content_length = value.trim().parse().ok()?;
+                }
+            }
+        }
+
+        let mut body = vec![0; content_length];
+        reader.read_exact(&mut body).ok()?;
+
+        Some(ReceivedRequest {
+            method,
+            path,
+            body: String::from_utf8_lossy(&body).into_owned(),
+        })
+    }
+}
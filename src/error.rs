@@ -0,0 +1,232 @@
+//! Errors produced by this crate.
+//!
+//! Both the resource types and the clients report failures through [`Error`],
+//! so callers can match on what went wrong instead of inspecting messages.
+
+use std::fmt;
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// The variants don't depend on the crate features, so that enabling a
+/// feature never breaks a `match` elsewhere. New variants may be added.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// An endpoint path was built with the wrong number of parameters.
+    PathParameters { expected: usize, provided: usize },
+    /// A URL could not be parsed or joined.
+    Url(url::ParseError),
+    /// A client was asked to send a request without a host to send it to.
+    NoHost,
+    /// The request could not be sent or its response could not be read.
+    Transport(TransportError),
+    /// Porta replied with a non-success HTTP status.
+    Status {
+        status: http::StatusCode,
+        body: String,
+    },
+    /// A JSON document did not match the expected type. `path` points to the
+    /// element that failed, ie. `services[2].service.backend_version`.
+    Deserialization {
+        path: String,
+        source: serde_json::Error,
+    },
+    /// A request body could not be serialized.
+    Serialization(serde_json::Error),
+    /// An environment that Porta doesn't know was used to build a path.
+    UnknownEnvironment,
+    /// A proxy config rollback between environments Porta can't promote.
+    InvalidRollback(String),
+    /// Several limits with different values were given for the same metric
+    /// and period, which Porta allows a single limit for.
+    ConflictingLimits {
+        metric_id: u64,
+        period: crate::api::v0::limit::Period,
+    },
+    /// A resource came without metadata.
+    MissingMetadata,
+    /// A resource's metadata lacks a link with the given relation.
+    MissingLink { rel: String },
+    /// An ActiveDocs body that is not an OpenAPI 2 or 3 document.
+    InvalidApiDoc(String),
+    /// Authentication provider settings that can't work for its kind.
+    InvalidAuthenticationProvider(String),
+    /// Policy configurations that do not match the schemas of their policies.
+    /// Only reported with the `policy-schemas` feature.
+    InvalidPolicyChain(Vec<crate::api::v0::service::proxy::policies::PolicyViolation>),
+}
+
+impl Error {
+    /// The HTTP status of the response, if the error originated from one.
+    pub fn status(&self) -> Option<http::StatusCode> {
+        match self {
+            Error::Status { status, .. } => Some(*status),
+            Error::Transport(e) => e.status(),
+            _ => None,
+        }
+    }
+
+    pub fn is_not_found(&self) -> bool {
+        self.status() == Some(http::StatusCode::NOT_FOUND)
+    }
+
+    /// Porta reports validation errors with a 422 status, with the details in
+    /// the response body.
+    pub fn is_validation(&self) -> bool {
+        self.status() == Some(http::StatusCode::UNPROCESSABLE_ENTITY)
+    }
+
+    /// The response body returned by Porta along with an error status.
+    pub fn body(&self) -> Option<&str> {
+        match self {
+            Error::Status { body, .. } => Some(body.as_str()),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::PathParameters { expected, provided } => write!(
+                f,
+                "required {} parameters but {} were provided",
+                expected, provided
+            ),
+            Error::Url(e) => write!(f, "invalid url: {}", e),
+            Error::NoHost => write!(f, "no url"),
+            Error::Transport(e) => write!(f, "transport error: {}", e),
+            Error::Status { status, body } => {
+                write!(f, "request failed with status {}: {}", status, body)
+            }
+            Error::Deserialization { path, source } => {
+                write!(f, "failed to deserialize {}: {}", path, source)
+            }
+            Error::Serialization(e) => write!(f, "failed to serialize request body: {}", e),
+            Error::UnknownEnvironment => write!(f, "unknown environment"),
+            Error::InvalidRollback(reason) => write!(f, "invalid rollback: {}", reason),
+            Error::ConflictingLimits { metric_id, period } => write!(
+                f,
+                "conflicting limits for metric {} and period {:?}",
+                metric_id, period
+            ),
+            Error::MissingMetadata => write!(f, "no metadata present"),
+            Error::MissingLink { rel } => write!(f, "no {} link present", rel),
+            Error::InvalidApiDoc(reason) => write!(f, "invalid api doc: {}", reason),
+            Error::InvalidAuthenticationProvider(reason) => {
+                write!(f, "invalid authentication provider: {}", reason)
+            }
+            Error::InvalidPolicyChain(violations) => {
+                write!(f, "invalid policy chain")?;
+                for (i, violation) in violations.iter().enumerate() {
+                    write!(f, "{} {}", if i == 0 { ":" } else { ";" }, violation)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Url(e) => Some(e),
+            Error::Transport(e) => e.source(),
+            Error::Deserialization { source, .. } => Some(source),
+            Error::Serialization(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<url::ParseError> for Error {
+    fn from(e: url::ParseError) -> Self {
+        Error::Url(e)
+    }
+}
+
+#[cfg(any(feature = "client", feature = "async-client"))]
+impl From<reqwest::Error> for Error {
+    fn from(e: reqwest::Error) -> Self {
+        Error::Transport(TransportError(e))
+    }
+}
+
+/// An error from the HTTP client. It is opaque so that `Error` looks the same
+/// with or without the client features.
+#[derive(Debug)]
+pub struct TransportError(TransportInner);
+
+#[cfg(any(feature = "client", feature = "async-client"))]
+type TransportInner = reqwest::Error;
+#[cfg(not(any(feature = "client", feature = "async-client")))]
+type TransportInner = std::convert::Infallible;
+
+impl TransportError {
+    pub fn status(&self) -> Option<http::StatusCode> {
+        #[cfg(any(feature = "client", feature = "async-client"))]
+        return self.0.status();
+        #[cfg(not(any(feature = "client", feature = "async-client")))]
+        match self.0 {}
+    }
+
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.0)
+    }
+}
+
+impl fmt::Display for TransportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl From<serde_path_to_error::Error<serde_json::Error>> for Error {
+    fn from(e: serde_path_to_error::Error<serde_json::Error>) -> Self {
+        Error::Deserialization {
+            path: e.path().to_string(),
+            source: e.into_inner(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, serde::Deserialize)]
+    #[allow(dead_code)]
+    struct Outer {
+        inner: Vec<Inner>,
+    }
+
+    #[derive(Debug, serde::Deserialize)]
+    #[allow(dead_code)]
+    struct Inner {
+        id: u64,
+    }
+
+    #[test]
+    fn it_reports_the_json_path_that_failed_to_deserialize() {
+        let json = r#"{ "inner": [ { "id": 1 }, { "id": "two" } ] }"#;
+        let de = &mut serde_json::Deserializer::from_str(json);
+        let err: Error = serde_path_to_error::deserialize::<_, Outer>(de)
+            .unwrap_err()
+            .into();
+        match err {
+            Error::Deserialization { ref path, .. } => assert_eq!(path, "inner[1].id"),
+            _ => panic!("unexpected error {:#?}", err),
+        }
+    }
+
+    #[test]
+    fn it_exposes_the_status_and_body() {
+        let err = Error::Status {
+            status: http::StatusCode::UNPROCESSABLE_ENTITY,
+            body: r#"{ "errors": { "name": ["can't be blank"] } }"#.into(),
+        };
+        assert!(err.is_validation());
+        assert!(!err.is_not_found());
+        assert!(err.body().unwrap().contains("can't be blank"));
+    }
+}
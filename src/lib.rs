@@ -10,20 +10,22 @@
 pub mod resources;
 
 pub mod api;
+pub mod error;
 
-#[cfg(client)]
+#[cfg(any(feature = "client", feature = "async-client"))]
 pub mod client;
 
 /// Public dependencies for downstream crate compatibility
 pub mod deps {
-    #[cfg(client)]
+    #[cfg(any(feature = "client", feature = "async-client"))]
     pub use reqwest;
     pub use url;
 }
 
-#[cfg(client)]
+#[cfg(any(feature = "client", feature = "async-client"))]
 pub use deps::reqwest;
 pub use deps::url;
+pub use error::Error;
 
 #[cfg(test)]
 mod tests {
@@ -1,4 +1,5 @@
 use super::path_builder::{ParameterQuantifier, PathBuilder};
+use crate::error::Result;
 use http::Method;
 use serde::{de::DeserializeOwned, Deserialize};
 
@@ -29,7 +30,7 @@ impl<'a, 's, M> Endpoint<'a, 's, M> {
         &self.path_builder
     }
 
-    pub fn path(&self, args: &[&str]) -> Result<String, Box<dyn std::error::Error>> {
+    pub fn path(&self, args: &[&str]) -> Result<String> {
         self.path_builder().build(args)
     }
 
@@ -39,32 +40,38 @@ impl<'a, 's, M> Endpoint<'a, 's, M> {
 }
 
 impl<'a, 's, M: DeserializeOwned> Endpoint<'a, 's, M> {
-    pub fn parse_reader(&self, r: impl std::io::Read) -> Result<M, Box<dyn std::error::Error>> {
-        let data = serde_json::from_reader(r)?;
-        Ok(data)
+    pub fn parse_reader(&self, r: impl std::io::Read) -> Result<M> {
+        parse_reader(r)
     }
 }
 
 impl<'a, 's, 'de, M: Deserialize<'de>> Endpoint<'a, 's, M> {
-    pub fn parse_str(&self, s: &'de str) -> Result<M, Box<dyn std::error::Error>> {
-        let data = serde_json::from_str(s)?;
+    pub fn parse_str(&self, s: &'de str) -> Result<M> {
+        let de = &mut serde_json::Deserializer::from_str(s);
+        let data = serde_path_to_error::deserialize(de)?;
         Ok(data)
     }
 }
 
+// Deserialize a response, keeping track of the JSON path on failure.
+pub(crate) fn parse_reader<M: DeserializeOwned>(r: impl std::io::Read) -> Result<M> {
+    let de = &mut serde_json::Deserializer::from_reader(r);
+    let data = serde_path_to_error::deserialize(de)?;
+    Ok(data)
+}
+
 macro_rules! endpoint_test {
     { $name:ident, $endpoint:path, $response:expr } => {
 
         #[cfg(test)]
-        const RESPONSE: &'static str = $response;
+        const RESPONSE: &str = $response;
 
         #[cfg(test)]
         #[test]
         fn $name() {
             let object = $endpoint.parse_str(RESPONSE);
-            match object {
-              Err(ref e) => println!("Error: {:#?}", e),
-              _ => (),
+            if let Err(ref e) = object {
+              println!("Error: {:#?}", e);
             }
             assert!(object.is_ok());
             let object = object.unwrap();
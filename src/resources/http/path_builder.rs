@@ -1,4 +1,4 @@
-use std::error::Error;
+use crate::error::{Error, Result};
 
 pub enum ParameterQuantifier {
     PairingSegments,
@@ -27,13 +27,12 @@ impl<'a, 's> PathBuilder<'a, 's> {
     }
 
     // Similar to what itertools::Itertools::zip_longest would do
-    pub fn build(&self, params: &[&str]) -> Result<String, Box<dyn Error>> {
+    pub fn build(&self, params: &[&str]) -> Result<String> {
         if params.len() != self.accepted_parameters() {
-            return Err(From::from(format!(
-                "required {} parameters but {} were provided",
-                self.accepted_parameters(),
-                params.len()
-            )));
+            return Err(Error::PathParameters {
+                expected: self.accepted_parameters(),
+                provided: params.len(),
+            });
         }
 
         let mut s = self.segments.iter().zip(params.iter()).fold(
@@ -98,6 +97,12 @@ mod tests {
     fn it_fails_with_less_params_than_specified() {
         let pb = PathBuilder::new(&["/services/", "/metrics/", "/list/"], PairingSegments);
         let s = pb.build(&["123456", "abc"]);
-        assert!(s.is_err());
+        assert!(matches!(
+            s,
+            Err(Error::PathParameters {
+                expected: 3,
+                provided: 2
+            })
+        ));
     }
 }
@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+use crate::error::Error;
+
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub struct Link {
     rel: String,
@@ -30,7 +32,7 @@ impl Link {
         self.href.as_str()
     }
 
-    pub fn url(&self) -> Result<crate::deps::url::Url, Box<dyn std::error::Error>> {
+    pub fn url(&self) -> crate::error::Result<crate::deps::url::Url> {
         let url = crate::deps::url::Url::parse(self.href())?;
         Ok(url)
     }
@@ -60,9 +62,9 @@ impl Metadata {
         self.links()?.iter().find(|link| link.rel() == rel)?.into()
     }
 
-    pub fn find_url(&self, rel: &str) -> Result<crate::deps::url::Url, Box<dyn std::error::Error>> {
+    pub fn find_url(&self, rel: &str) -> crate::error::Result<crate::deps::url::Url> {
         self.find_link(rel)
-            .ok_or(format!("no {} link present", rel))?
+            .ok_or_else(|| Error::MissingLink { rel: rel.into() })?
             .url()
     }
 }
@@ -74,7 +76,7 @@ mod tests {
     mod links {
         use super::*;
 
-        const FIXTURE: &'static str = r#"
+        const FIXTURE: &str = r#"
             [
                 {
                 "rel": "service",
@@ -120,7 +122,7 @@ mod tests {
     mod metadata {
         use super::*;
 
-        const FIXTURE: &'static str = r#"{
+        const FIXTURE: &str = r#"{
             "created_at": "2020-05-11T13:55:00+01:00",
             "updated_at": "2020-05-11T13:55:00+01:00",
             "links": [
@@ -142,9 +144,8 @@ mod tests {
         #[test]
         fn it_parses_metadata() {
             let metadata = parse_metadata(FIXTURE);
-            match &metadata {
-                Err(e) => println!("Error parsing metadata {:#?}", e),
-                _ => (),
+            if let Err(e) = &metadata {
+                println!("Error parsing metadata {:#?}", e);
             }
             assert!(metadata.is_ok());
         }
@@ -171,11 +172,17 @@ mod tests {
         fn it_converts_a_link_to_a_url() {
             let metadata = parse_metadata(FIXTURE).expect("can't parse properly");
             let url = metadata.find_url("service");
-            match &url {
-                Err(e) => println!("Error converting a link to a URL {:#?}", e),
-                _ => (),
+            if let Err(e) = &url {
+                println!("Error converting a link to a URL {:#?}", e);
             }
             assert!(url.is_ok());
         }
+
+        #[test]
+        fn it_fails_to_convert_a_non_existing_link_to_a_url() {
+            let metadata = parse_metadata(FIXTURE).expect("can't parse properly");
+            let url = metadata.find_url("non_existing");
+            assert!(matches!(url, Err(Error::MissingLink { rel }) if rel == "non_existing"));
+        }
     }
 }
@@ -1,5 +1,6 @@
 #[macro_use]
 pub mod http;
 pub mod metadata;
+pub mod pagination;
 
 pub use metadata::Metadata;
@@ -0,0 +1,11 @@
+/// Marks the response type of a list endpoint that Porta paginates via the
+/// `page` and `per_page` query parameters.
+///
+/// Clients use this to walk all pages of such an endpoint, yielding the
+/// inner items one at a time.
+pub trait Paginated: Into<Vec<<Self as Paginated>::Item>> {
+    type Item;
+}
+
+/// The maximum (and default) page size accepted by Porta.
+pub const MAX_PER_PAGE: u32 = 500;